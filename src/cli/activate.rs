@@ -0,0 +1,189 @@
+// src/cli/activate.rs
+// Shell 集成：`enman activate <shell>` 输出 hook 脚本，hook 在每次显示提示符或切换目录时
+// 调用隐藏命令 `enman hook-env`，由它输出当前目录需要的环境变更；
+// 同时定义同名的 enman 函数，让 `enman shell` 的输出在当前 shell 中生效
+use crate::core::environment::{self, EnvChanges};
+use crate::core::paths::EnvManPaths;
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+#[derive(Args)]
+pub struct ActivateArgs {
+    /// Shell to generate the hook for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Args)]
+pub struct HookEnvArgs {
+    #[arg(long, value_enum)]
+    pub shell: Shell,
+}
+
+pub fn run(args: ActivateArgs) -> Result<()> {
+    let enman = std::env::current_exe()?.display().to_string();
+    print!("{}", hook_script(args.shell, &enman));
+    Ok(())
+}
+
+pub fn run_hook_env(args: HookEnvArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let cwd = std::env::current_dir()?;
+    let changes = environment::hook_changes(&paths, &cwd);
+    print!("{}", render(args.shell, &changes));
+    Ok(())
+}
+
+fn hook_script(shell: Shell, enman: &str) -> String {
+    match shell {
+        Shell::Bash => format!(
+            r#"_enman_hook() {{
+  local previous_exit_status=$?
+  eval "$({enman} hook-env --shell bash)"
+  return $previous_exit_status
+}}
+if [[ ";${{PROMPT_COMMAND[*]:-}};" != *";_enman_hook;"* ]]; then
+  PROMPT_COMMAND="_enman_hook${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+fi
+_enman_hook
+enman() {{
+  if [[ "$1" == shell ]]; then
+    shift
+    eval "$({enman} shell --shell bash "$@")"
+    _enman_hook
+  else
+    command {enman} "$@"
+  fi
+}}
+"#,
+            enman = quote_posix(enman)
+        ),
+        Shell::Zsh => format!(
+            r#"_enman_hook() {{
+  eval "$({enman} hook-env --shell zsh)"
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook precmd _enman_hook
+add-zsh-hook chpwd _enman_hook
+_enman_hook
+enman() {{
+  if [[ "$1" == shell ]]; then
+    shift
+    eval "$({enman} shell --shell zsh "$@")"
+    _enman_hook
+  else
+    command {enman} "$@"
+  fi
+}}
+"#,
+            enman = quote_posix(enman)
+        ),
+        Shell::Fish => format!(
+            r#"function __enman_hook --on-variable PWD --on-event fish_prompt
+    {enman} hook-env --shell fish | source
+end
+__enman_hook
+function enman
+    if test (count $argv) -gt 0; and test "$argv[1]" = shell
+        {enman} shell --shell fish $argv[2..-1] | source
+        __enman_hook
+    else
+        command {enman} $argv
+    end
+end
+"#,
+            enman = quote_fish(enman)
+        ),
+        Shell::Powershell => format!(
+            // 再次加载时 prompt 已经是 enman 的函数，只在第一次记录原来的 prompt，否则会递归调用自身
+            r#"if (-not (Test-Path variable:global:__EnmanOriginalPrompt)) {{
+    $global:__EnmanOriginalPrompt = $function:prompt
+}}
+function global:prompt {{
+    & {enman} hook-env --shell powershell | Out-String | Invoke-Expression
+    & $global:__EnmanOriginalPrompt
+}}
+& {enman} hook-env --shell powershell | Out-String | Invoke-Expression
+function global:enman {{
+    if ($args.Count -gt 0 -and $args[0] -eq 'shell') {{
+        & {enman} shell --shell powershell @($args | Select-Object -Skip 1) | Out-String | Invoke-Expression
+        & {enman} hook-env --shell powershell | Out-String | Invoke-Expression
+    }} else {{
+        & {enman} @args
+    }}
+}}
+"#,
+            enman = quote_powershell(enman)
+        ),
+    }
+}
+
+/// 把环境变更渲染为对应 shell 的语句
+pub fn render(shell: Shell, changes: &EnvChanges) -> String {
+    let mut out = String::new();
+    for name in &changes.unset {
+        out.push_str(&match shell {
+            Shell::Bash | Shell::Zsh => format!("unset {};\n", name),
+            Shell::Fish => format!("set -e {};\n", name),
+            Shell::Powershell => format!("Remove-Item Env:{} -ErrorAction SilentlyContinue\n", name),
+        });
+    }
+    for (name, value) in &changes.set {
+        out.push_str(&match shell {
+            Shell::Bash | Shell::Zsh => format!("export {}={};\n", name, quote_posix(value)),
+            // fish 的 PATH 是列表，需要拆开设置
+            Shell::Fish if name == "PATH" => {
+                let entries: Vec<String> = std::env::split_paths(value)
+                    .map(|p| quote_fish(&p.display().to_string()))
+                    .collect();
+                format!("set -gx PATH {};\n", entries.join(" "))
+            }
+            Shell::Fish => format!("set -gx {} {};\n", name, quote_fish(value)),
+            Shell::Powershell => format!("$env:{} = {}\n", name, quote_powershell(value)),
+        });
+    }
+    out
+}
+
+fn quote_posix(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+fn quote_fish(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+fn quote_powershell(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_changes() {
+        let changes = EnvChanges {
+            set: vec![("JAVA_HOME".to_string(), "/opt/it's/jdk".to_string())],
+            unset: vec!["VIRTUAL_ENV".to_string()],
+        };
+        assert_eq!(render(Shell::Bash, &changes), "unset VIRTUAL_ENV;\nexport JAVA_HOME='/opt/it'\\''s/jdk';\n");
+        assert_eq!(render(Shell::Fish, &changes), "set -e VIRTUAL_ENV;\nset -gx JAVA_HOME '/opt/it\\'s/jdk';\n");
+        assert_eq!(
+            render(Shell::Powershell, &changes),
+            "Remove-Item Env:VIRTUAL_ENV -ErrorAction SilentlyContinue\n$env:JAVA_HOME = '/opt/it''s/jdk'\n"
+        );
+
+        // 重复加载 profile 时不能把 enman 的 prompt 记录为原来的 prompt
+        let script = hook_script(Shell::Powershell, "enman");
+        assert!(script.starts_with("if (-not (Test-Path variable:global:__EnmanOriginalPrompt)) {\n"));
+    }
+}
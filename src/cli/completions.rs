@@ -0,0 +1,144 @@
+// src/cli/completions.rs
+// 命令行补全：`enman completions <shell>` 输出注册脚本，按 Tab 时 shell 以
+// _ENMAN_COMPLETE=<shell> 重新调用 enman，由 clap_complete 根据 Commands 的定义补全子命令和参数，
+// tool@version 参数由下面的补全函数提供候选（已安装版本 + 缓存的远程版本，不访问网络）
+use crate::cli::activate::Shell;
+use crate::core::paths::EnvManPaths;
+use crate::core::{resolve, version};
+use crate::downloader;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+use std::ffi::OsStr;
+
+/// 触发补全的环境变量，见 main 中的 CompleteEnv
+pub const COMPLETE_VAR: &str = "_ENMAN_COMPLETE";
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+pub fn run(args: CompletionsArgs) -> Result<()> {
+    let name = match args.shell {
+        Shell::Bash => "bash",
+        Shell::Zsh => "zsh",
+        Shell::Fish => "fish",
+        Shell::Powershell => "powershell",
+    };
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(name)
+        .ok_or_else(|| anyhow!("Completions are not supported for {}", name))?;
+
+    let exe = std::env::current_exe()?;
+    // enman 和 em 都可以生成补全，补全的命令名与生成时使用的程序一致
+    let bin = exe.file_stem().and_then(OsStr::to_str).unwrap_or("enman").to_string();
+    completer.write_registration(COMPLETE_VAR, &bin, &bin, &exe.to_string_lossy(), &mut std::io::stdout())?;
+    Ok(())
+}
+
+/// 补全工具名：支持的工具和已安装的工具
+pub fn complete_tool(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    tool_names()
+        .into_iter()
+        .filter(|tool| tool.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// 补全当前目录 .enmanrc 中定义的任务名，说明作为帮助信息
+pub fn complete_task(current: &OsStr) -> Vec<CompletionCandidate> {
+    let (Some(current), Ok(cwd)) = (current.to_str(), std::env::current_dir()) else {
+        return Vec::new();
+    };
+    resolve::layered_config(&cwd)
+        .config
+        .tasks
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(current))
+        .map(|(name, task)| CompletionCandidate::new(name).help(task.description.map(Into::into)))
+        .collect()
+}
+
+/// 补全 tool@version
+///
+/// 输入中还没有 @ 时补全工具名；只剩一个工具匹配时直接列出它的所有版本，
+/// 否则 bash 会在 `node@` 后面加上空格。
+pub fn complete_tool_version(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let Ok(paths) = EnvManPaths::new() else {
+        return Vec::new();
+    };
+
+    let tools: Vec<String> = match current.split_once('@') {
+        Some((tool, _)) => vec![tool.to_string()],
+        None => tool_names().into_iter().filter(|tool| tool.starts_with(current)).collect(),
+    };
+    if tools.len() > 1 {
+        return tools.into_iter().map(|tool| CompletionCandidate::new(format!("{}@", tool))).collect();
+    }
+
+    tools
+        .iter()
+        .flat_map(|tool| {
+            version_candidates(&paths, tool)
+                .into_iter()
+                .map(move |(version, help)| (format!("{}@{}", tool, version), help))
+        })
+        .filter(|(value, _)| value.starts_with(current))
+        .map(|(value, help)| CompletionCandidate::new(value).help(help.map(Into::into)))
+        .collect()
+}
+
+fn tool_names() -> Vec<String> {
+    match EnvManPaths::new() {
+        Ok(paths) => resolve::known_tools(&paths),
+        Err(_) => downloader::SUPPORTED_TOOLS.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+/// 某个工具可补全的版本：已安装的版本在前（标注 installed），然后是缓存的远程版本和别名
+fn version_candidates(paths: &EnvManPaths, tool: &str) -> Vec<(String, Option<&'static str>)> {
+    let mut installed = paths.installed_versions(tool);
+    installed.sort_by(|a, b| version::compare_versions(b, a));
+
+    let mut candidates: Vec<(String, Option<&'static str>)> =
+        installed.iter().map(|v| (v.clone(), Some("installed"))).collect();
+    candidates.extend(
+        downloader::cached_versions(paths, tool)
+            .into_iter()
+            .filter(|v| !installed.contains(v))
+            .map(|v| (v, None)),
+    );
+    candidates.push(("latest".to_string(), None));
+    if version::has_lts_line(tool) {
+        candidates.push(("lts".to_string(), None));
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_candidates() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(tmp.path().join(".enman"));
+        std::fs::create_dir_all(paths.install_dir("node").join("18.17.0")).unwrap();
+        std::fs::create_dir_all(paths.remote_versions_cache("node").parent().unwrap()).unwrap();
+        std::fs::write(paths.remote_versions_cache("node"), "20.5.0\n18.17.0\n").unwrap();
+
+        let versions: Vec<String> = version_candidates(&paths, "node").into_iter().map(|(v, _)| v).collect();
+        assert_eq!(versions, vec!["18.17.0", "20.5.0", "latest", "lts"]);
+    }
+}
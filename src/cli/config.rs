@@ -1,7 +1,14 @@
-use anyhow::Result;
+use crate::cli::install;
+use crate::core::enmanrc::{self, LayeredConfig, ProjectConfig, Severity};
+use crate::core::paths::EnvManPaths;
+use crate::core::settings::{self, GlobalSettings};
+use crate::core::{lock, resolve, shim_manager, version};
+use crate::downloader;
+use anyhow::{bail, Result};
 use clap::Args;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct ConfigArgs {
@@ -12,77 +19,371 @@ pub struct ConfigArgs {
 #[derive(clap::Subcommand)]
 pub enum ConfigCommand {
     /// Show current project configuration
-    Show,
-    /// Apply configuration from .enmanrc
+    Show {
+        /// Show the result of merging every .enmanrc from the project root down, with the file each value came from
+        #[arg(long)]
+        resolved: bool,
+    },
+    /// Apply configuration from .enmanrc (merged from the project root down)
     Apply,
     /// Initialize a new .enmanrc file
     Init,
+    /// Check .enmanrc for unknown tools, invalid versions and unsupported keys
+    Validate {
+        /// Configuration file to check (defaults to .enmanrc in the current directory)
+        path: Option<PathBuf>,
+    },
+    /// Set a global setting in enman.json
+    Set {
+        /// Setting name: language, mirrors.<tool>, auto_install, cache_dir, proxy or log_level
+        key: String,
+        value: String,
+        #[command(flatten)]
+        scope: Scope,
+    },
+    /// Print the value of a global setting
+    Get {
+        key: String,
+        #[command(flatten)]
+        scope: Scope,
+    },
+    /// Remove a global setting
+    Unset {
+        key: String,
+        #[command(flatten)]
+        scope: Scope,
+    },
+    /// List all global settings
+    List {
+        #[command(flatten)]
+        scope: Scope,
+    },
 }
 
-pub fn run(args: ConfigArgs) -> Result<()> {
+/// 设置的作用范围；目前只支持全局设置，项目设置写在 .enmanrc 的 `[settings]` 中
+#[derive(Args)]
+pub struct Scope {
+    /// Operate on the global settings file (enman.json)
+    #[arg(long, required = true)]
+    global: bool,
+}
+
+pub async fn run(args: ConfigArgs) -> Result<()> {
     match args.command {
-        ConfigCommand::Show => show_config()?,
-        ConfigCommand::Apply => apply_config()?,
+        ConfigCommand::Show { resolved: false } => show_config()?,
+        ConfigCommand::Show { resolved: true } => show_resolved_config()?,
+        ConfigCommand::Apply => apply_config().await?,
         ConfigCommand::Init => init_config()?,
+        ConfigCommand::Validate { path } => validate_config(path)?,
+        ConfigCommand::Set { key, value, .. } => {
+            let path = settings::file_path()?;
+            let mut global = GlobalSettings::load(&path)?;
+            global.set(&key, &value)?;
+            global.save(&path)?;
+            println!("✅ Set {} = {} in {}", key, global.get(&key)?.unwrap_or_default(), path.display());
+        }
+        ConfigCommand::Get { key, .. } => {
+            let global = GlobalSettings::load(&settings::file_path()?)?;
+            match global.get(&key)? {
+                Some(value) => println!("{}", value),
+                None => bail!("'{}' is not set", key),
+            }
+        }
+        ConfigCommand::Unset { key, .. } => {
+            let path = settings::file_path()?;
+            let mut global = GlobalSettings::load(&path)?;
+            if global.unset(&key)? {
+                global.save(&path)?;
+                println!("✅ Removed {} from {}", key, path.display());
+            } else {
+                println!("{} is not set", key);
+            }
+        }
+        ConfigCommand::List { .. } => {
+            let global = GlobalSettings::load(&settings::file_path()?)?;
+            for (key, value) in global.list() {
+                println!("{} = {}", key, value);
+            }
+        }
     }
     Ok(())
 }
 
 fn show_config() -> Result<()> {
-    let config_path = std::env::current_dir()?.join(".enmanrc");
+    let config_path = std::env::current_dir()?.join(enmanrc::FILE_NAME);
     if !config_path.exists() {
         println!("Config file not found: {}", config_path.display());
         return Ok(());
     }
 
-    let content = fs::read_to_string(&config_path)?;
-    let config: HashMap<String, String> = toml::from_str(&content)?;
-
-    println!("Tools configured in {}:", config_path.display());
-    for (tool, version) in &config {
-        println!("  {}: {}", tool, version);
+    let config = ProjectConfig::load(&config_path)?;
+    for section in &config.invalid_sections {
+        settings::warn(format_args!("ignoring invalid [{}]. Run `enman config validate` for details", section));
     }
+    // 应用选中的配置方案
+    let config = LayeredConfig::merge(vec![(config_path.clone(), config)]).config;
 
-    if config.is_empty() {
+    if config.tools.is_empty() {
         println!("No tools configured in {}", config_path.display());
+    } else {
+        println!("Tools configured in {}:", config_path.display());
+        for (tool, version) in &config.tools {
+            println!("  {}: {}", tool, version);
+        }
+    }
+
+    if !config.services.is_empty() {
+        println!("Services:");
+        for (name, service) in &config.services {
+            match service.port {
+                Some(port) => println!("  {} (port {})", name, port),
+                None => println!("  {}", name),
+            }
+        }
     }
 
     Ok(())
 }
 
-fn apply_config() -> Result<()> {
-    let config_path = std::env::current_dir()?.join(".enmanrc");
-    if !config_path.exists() {
-        println!("Config file not found: {}", config_path.display());
+/// 输出逐层合并后的配置，每个值后面注明来自哪个文件
+fn show_resolved_config() -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let layered = resolve::layered_config(&cwd);
+    if layered.files.is_empty() {
+        println!("No {} found in {} or its parent directories", enmanrc::FILE_NAME, cwd.display());
         return Ok(());
     }
 
-    let content = fs::read_to_string(&config_path)?;
-    let config: HashMap<String, String> = toml::from_str(&content)?;
+    println!("Files (outermost first):");
+    for file in &layered.files {
+        println!("  {}", file.display());
+    }
+    print_profile(&layered);
+
+    let config = &layered.config;
+    let origin = |section: &str, key: &str| match layered.origin(section, key) {
+        Some(origin) => match origin.line {
+            Some(line) => format!("{}:{}", origin.file.display(), line),
+            None => origin.file.display().to_string(),
+        },
+        None => String::new(),
+    };
+    // 输出为带注释的 TOML，可以直接复制回 .enmanrc
+    let quoted = |value: &String| toml::Value::String(value.clone()).to_string();
+    print_section("tools", config.tools.iter().map(|(k, v)| (k.clone(), quoted(v), origin("tools", k))).collect());
+    print_section("env", config.env.iter().map(|(k, v)| (k.clone(), quoted(v), origin("env", k))).collect());
+    let services = config.services.iter().map(|(name, service)| {
+        let value = service.port.map(|port| format!("{{ port = {} }}", port)).unwrap_or_else(|| "true".to_string());
+        (name.clone(), value, origin("services", name))
+    });
+    print_section("services", services.collect());
+    print_section("tasks", config.tasks.iter().map(|(k, task)| (k.clone(), quoted(&task.run), origin("tasks", k))).collect());
+    let settings = config.settings.entries().into_iter().map(|(k, v)| (k.to_string(), v, origin("settings", k)));
+    print_section("settings", settings.collect());
+    Ok(())
+}
+
+/// 输出选择的配置方案；没有任何文件定义它时给出警告
+fn print_profile(layered: &LayeredConfig) {
+    let Some(profile) = enmanrc::active_profile() else {
+        return;
+    };
+    println!("Profile: {}", profile);
+    if layered.undefined_profile().is_some() {
+        settings::warn(format_args!("profile '{}' is not defined in any {}", profile, enmanrc::FILE_NAME));
+    }
+}
+
+/// 输出一节合并后的配置，entries 为 (键, 值, 来源)
+fn print_section(section: &str, entries: Vec<(String, String, String)>) {
+    if entries.is_empty() {
+        return;
+    }
+    println!("[{}]", section);
+    let width = entries.iter().map(|(k, v, _)| k.len() + v.len() + 3).max().unwrap_or(0);
+    for (key, value, origin) in entries {
+        println!("  {:width$}  # {}", format!("{} = {}", key, value), origin, width = width);
+    }
+}
+
+/// apply 中每一步（安装一个工具或启动一个服务）的结果
+struct Step {
+    name: String,
+    outcome: Result<String>,
+}
+
+/// 安装 .enmanrc 中缺失的工具版本、创建 shim、启动声明的服务，最后输出每一步的结果
+///
+/// 使用从项目根目录到当前目录逐层合并后的配置。某一步失败不会中断其他步骤；
+/// 只要有失败，命令以非零退出码结束。
+async fn apply_config() -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let layered = resolve::layered_config(&cwd);
+    if layered.files.is_empty() {
+        let config_path = cwd.join(enmanrc::FILE_NAME);
+        bail!("Config file not found: {}. Create one with `enman config init`", config_path.display());
+    }
+
+    let paths = EnvManPaths::new()?;
+    let files: Vec<String> = layered.files.iter().map(|file| file.display().to_string()).collect();
+    println!("Applying configuration from {}:", files.join(", "));
+    print_profile(&layered);
+    let config = layered.config;
+
+    let mut steps = Vec::new();
+    // 成功应用的工具 → 精确版本，启动服务时使用
+    let mut applied = BTreeMap::new();
+    for (tool, requested) in &config.tools {
+        let outcome = apply_tool(&paths, tool, requested).await;
+        if let Ok((version, _)) = &outcome {
+            applied.insert(tool.clone(), version.clone());
+        }
+        steps.push(Step {
+            name: tool.clone(),
+            outcome: outcome.map(|(version, status)| format!("{} ({})", version, status)),
+        });
+    }
+
+    for (service, service_config) in &config.services {
+        let outcome = start_service(&paths, &cwd, service, service_config.port, &config, &applied);
+        steps.push(Step {
+            name: format!("{} (service)", service),
+            outcome,
+        });
+    }
 
-    println!("Applying configuration from {}:", config_path.display());
-    for (tool, version) in &config {
-        println!("  Setting {} to version {}", tool, version);
-        // 这里需要调用实际的use命令来设置工具版本
-        // 目前只是模拟输出
+    println!();
+    println!("Summary:");
+    let width = steps.iter().map(|step| step.name.len()).max().unwrap_or(0);
+    for step in &steps {
+        match &step.outcome {
+            Ok(message) => println!("  ✅ {:width$}  {}", step.name, message, width = width),
+            Err(e) => println!("  ❌ {:width$}  {:#}", step.name, e, width = width),
+        }
     }
 
+    let failed = steps.iter().filter(|step| step.outcome.is_err()).count();
+    if failed > 0 {
+        bail!("{} of {} steps failed", failed, steps.len());
+    }
     println!("Configuration applied successfully!");
     Ok(())
 }
 
+/// 解析并安装一个工具，返回 (精确版本, 状态说明)
+async fn apply_tool(paths: &EnvManPaths, tool: &str, requested: &str) -> Result<(String, String)> {
+    if version::is_system(requested) {
+        return Ok((version::SYSTEM.to_string(), "using the system installation".to_string()));
+    }
+
+    // .enman.lock 中锁定的版本满足要求时 version::resolve 会使用锁定的版本
+    let version = version::resolve(paths, tool, requested).await?;
+    let locked = lock::locked(tool).is_some_and(|entry| entry.version == version);
+    println!("  Setting {} to version {}{}", tool, version, if locked { " (locked)" } else { "" });
+
+    let status = if install::ensure_installed(paths, tool, &version).await? {
+        "installed"
+    } else {
+        // 已安装的版本也重建一次 shim，确保命令可用
+        shim_manager::create_shims(paths, tool, &version)?;
+        "already installed"
+    };
+    Ok((version, if locked { format!("{}, locked", status) } else { status.to_string() }))
+}
+
+/// 启动服务：优先使用 .enmanrc 中应用的版本，否则使用当前目录生效的版本
+fn start_service(
+    paths: &EnvManPaths,
+    cwd: &Path,
+    service: &str,
+    port: Option<u16>,
+    config: &ProjectConfig,
+    applied: &BTreeMap<String, String>,
+) -> Result<String> {
+    let version = match applied.get(service) {
+        Some(version) => version.clone(),
+        None if config.tools.contains_key(service) => bail!("{} was not installed", service),
+        None => match resolve::resolve(paths, service, cwd) {
+            Some(resolution) if resolution.installed => resolution.version,
+            Some(resolution) => bail!("{}@{} is not installed", service, resolution.requested),
+            None => bail!("no version of {} is set; add it to [tools]", service),
+        },
+    };
+    if version::is_system(&version) {
+        bail!("{} is set to 'system'; start the system service yourself", service);
+    }
+
+    let started = downloader::start_service(service, &paths.install_dir(service).join(&version), port)?;
+    let status = if started { "started" } else { "already running" };
+    Ok(match port {
+        Some(port) => format!("{} {} on port {}", version, status, port),
+        None => format!("{} {}", version, status),
+    })
+}
+
+/// 以 `文件:行:列: error: 说明` 的格式输出所有问题，有错误时以非零退出码结束
+fn validate_config(path: Option<PathBuf>) -> Result<()> {
+    let config_path = match path {
+        Some(path) => path,
+        None => std::env::current_dir()?.join(enmanrc::FILE_NAME),
+    };
+    let content = fs::read_to_string(&config_path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", config_path.display(), e))?;
+
+    let diagnostics = enmanrc::validate(&content);
+    for diagnostic in &diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        eprintln!("{}:{}:{}: {}: {}", config_path.display(), diagnostic.line, diagnostic.column, severity, diagnostic.message);
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    if errors > 0 {
+        bail!("{} has {} error(s)", config_path.display(), errors);
+    }
+    println!("✅ {} is valid", config_path.display());
+    Ok(())
+}
+
 fn init_config() -> Result<()> {
-    let config_path = std::env::current_dir()?.join(".enmanrc");
+    let config_path = std::env::current_dir()?.join(enmanrc::FILE_NAME);
     if config_path.exists() {
         println!("Config file already exists: {}", config_path.display());
         return Ok(());
     }
 
     let default_config = r#"# EnMan configuration
+# .enmanrc files in parent directories are merged with this one;
+# set `root = true` in the top-level file of a repository to stop the search there
+# root = true
+
 # Add your tools and their versions here
-# Example:
+[tools]
 # node = "16.14.0"
 # python = "3.10.0"
+
+# Environment variables exported by shims, `enman exec` and the shell hook
+[env]
+# JAVA_HOME = "{{tools.java.home}}"
+# DATABASE_URL = "mysql://root@127.0.0.1:{{services.mysql.port}}/app"
+
+# Tasks run by `enman run <task>`
+[tasks]
+# test = "npm test"
+# migrate = { run = "python manage.py migrate", depends = ["db"] }
+
+# Services started by `enman config apply`
+[services]
+# mysql = true
+# redis = { port = 6379 }
+
+# Profiles override [tools], [env] and [services] when selected with
+# `--profile <name>` or ENMAN_PROFILE=<name>
+# [profiles.ci]
+# tools = { node = "18" }
+# env = { APP_ENV = "test" }
 "#;
     
     fs::write(&config_path, default_config)?;
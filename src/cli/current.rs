@@ -0,0 +1,67 @@
+// src/cli/current.rs
+use crate::core::paths::EnvManPaths;
+use crate::core::resolve::{self, Resolution};
+use crate::core::shim;
+use crate::downloader;
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct CurrentArgs {
+    /// Optional: tool name (e.g., 'node'). If omitted, show every tool with a version set.
+    #[arg(value_name = "TOOL", add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool), help = crate::tr!("arg_tool_help"))]
+    pub tool: Option<String>,
+}
+
+pub fn run(args: CurrentArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+
+    let tools = match args.tool {
+        Some(tool) => vec![tool],
+        None => resolve::known_tools(&paths),
+    };
+
+    let mut shown = 0;
+    for tool in &tools {
+        match resolve::resolve_current(&paths, tool)? {
+            Some(resolution) => {
+                let binary = match main_binary(&paths, &resolution) {
+                    Some(binary) => binary.display().to_string(),
+                    None if resolution.is_system() => format!("({})", crate::tr!("not found on PATH")),
+                    None => format!("({})", crate::tr!("not installed")),
+                };
+                println!("{:<8} {:<12} {}  {}", tool, resolution.version, resolution.source, binary);
+                shown += 1;
+            }
+            None if tools.len() == 1 => println!("{:<8} {}", tool, crate::tr!("No version set")),
+            None => {}
+        }
+    }
+
+    if shown == 0 && tools.len() > 1 {
+        println!("{}", crate::tr!("No versions set."));
+    }
+    Ok(())
+}
+
+/// 工具主程序的完整路径：声明的可执行文件中第一个实际存在的（python 可能只有 python3）
+///
+/// 设置为 system 时在 PATH 上查找；版本未安装时返回 None。
+fn main_binary(paths: &EnvManPaths, resolution: &Resolution) -> Option<PathBuf> {
+    let tool = resolution.tool.as_str();
+    let declared = downloader::executables(tool);
+    let names = if declared.is_empty() { &[tool][..] } else { declared };
+
+    if resolution.is_system() {
+        return names.iter().find_map(|exe| shim::system_executable(paths, exe));
+    }
+    if !resolution.installed {
+        return None;
+    }
+    names
+        .iter()
+        .map(|exe| resolution.binary(exe))
+        .find(|binary| binary.is_file())
+        .or_else(|| Some(resolution.binary(names[0])))
+}
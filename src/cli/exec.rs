@@ -0,0 +1,45 @@
+// src/cli/exec.rs
+use crate::cli::install;
+use crate::core::paths::EnvManPaths;
+use crate::core::{environment, shim, version};
+use anyhow::{bail, Result};
+use clap::Args;
+use std::process::Command;
+
+#[derive(Args)]
+pub struct ExecArgs {
+    /// Tools and versions to run with (e.g., "node@18", "java@17")
+    #[arg(required = true, value_parser = crate::cli::parse_tool_version, add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool_version))]
+    pub tools: Vec<(String, String)>,
+
+    /// Command to run, after `--`
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+/// 在指定的工具版本下运行命令，不修改任何版本文件
+///
+/// 缺失的版本先安装；PATH 最前面是这些版本的 bin 目录，其余工具仍使用项目中生效的版本。
+/// 命令的退出码原样返回。
+pub async fn run(args: ExecArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let cwd = std::env::current_dir()?;
+
+    let mut overrides = Vec::new();
+    for (tool, requested) in args.tools {
+        if version::is_system(&requested) {
+            bail!("'system' cannot be used with exec; run the command directly to use the {} on your PATH", tool);
+        }
+        let version = version::resolve(&paths, &tool, &requested).await?;
+        install::ensure_installed(&paths, &tool, &version).await?;
+        overrides.push((tool, version));
+    }
+
+    let tool_env = environment::tool_env_with(&paths, &cwd, &overrides);
+    let path = tool_env.path_var(std::env::var_os("PATH").as_deref());
+
+    let (program, program_args) = args.command.split_first().expect("clap requires a command");
+    let mut command = Command::new(program);
+    command.args(program_args).envs(&tool_env.vars).env("PATH", path);
+    shim::exec(command, program)
+}
@@ -1,14 +1,14 @@
 use crate::core::paths;
+use crate::core::settings::{self, GlobalSettings};
 use anyhow::Result;
 use clap::Args;
-use std::fs;
 
 #[derive(Args)]
 pub struct InitArgs {}
 
 pub fn run(_args: InitArgs) -> Result<()> {
     let env_paths = paths::EnvManPaths::new()?;
-    let config_path = env_paths.root.join("enman.json");
+    let config_path = env_paths.settings_file();
     
     if config_path.exists() {
         println!("Config file already exists: {}", config_path.display());
@@ -17,11 +17,12 @@ pub fn run(_args: InitArgs) -> Result<()> {
     }
 
     // 创建基本配置文件
-    fs::create_dir_all(&env_paths.root)?;
-    fs::write(&config_path, "{}")?;
+    env_paths.ensure_dirs()?;
+    GlobalSettings::default().save(&config_path)?;
 
     println!("Created new config file: {}", config_path.display());
-    println!("You can now add tools and their versions to manage per project");
+    println!("Change global settings with: enman config set --global <key> <value>");
+    println!("Available keys: {}", settings::KEYS.join(", "));
 
     Ok(())
 }
\ No newline at end of file
@@ -1,45 +1,62 @@
 // src/cli/install.rs
-use crate::core::paths;
-use crate::downloader;
+use crate::core::paths::EnvManPaths;
+use crate::core::{lock, paths, shim_manager, version};
+use crate::downloader::{self, artifact::Artifact};
 use anyhow::Result;
 use clap::Args;
-use std::fs;
 
 #[derive(Args)]
 pub struct InstallArgs {
-    /// Tool and version to install (e.g., "node@16.14.0")
-    #[arg(value_parser = crate::cli::parse_tool_version)]
+    /// Tool and version to install (e.g., "node@16.14.0", "node@18", "node@lts")
+    #[arg(value_parser = crate::cli::parse_tool_version, add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool_version))]
     pub tool: (String, String),
 }
 
 pub async fn run(args: InstallArgs) -> Result<()> {
-    let (tool, version) = args.tool;
+    let (tool, requested) = args.tool;
+    if version::is_system(&requested) {
+        anyhow::bail!("'system' refers to the {} already on your PATH and cannot be installed", tool);
+    }
     
     let env_paths = paths::EnvManPaths::new()?;
-    let install_dir = env_paths.install_dir(&tool);
-    let install_path = install_dir.join(&version);
-    
-    // 检查是否已安装
-    if install_path.exists() {
+    let version = version::resolve(&env_paths, &tool, &requested).await?;
+    if version != requested {
+        println!("Resolved {}@{} to {}", tool, requested, version);
+    }
+    if !ensure_installed(&env_paths, &tool, &version).await? {
         println!("{} @ {} already installed", tool, version);
         return Ok(());
     }
-    
-    println!("Installing {} {}", tool, version);
+    println!("Installed {} {} successfully!", tool, version);
 
-    // 安装工具
-    downloader::install(&tool.to_lowercase(), &version, &install_path).await?;
+    Ok(())
+}
 
-    // 创建shim
-    let shims_dir = env_paths.root.join("shims");
-    fs::create_dir_all(&shims_dir)?;
-    
-    let shim_exe = shims_dir.join(format!("{}.exe", tool));
-    // 复制当前可执行文件到shim位置
-    let current_exe = std::env::current_exe()?;
-    fs::copy(current_exe, &shim_exe)?;
+/// 精确版本未安装时安装它并创建 shim，返回是否进行了安装
+///
+/// install、use、global、exec、lock、config apply 和 shim 的自动安装都通过这里安装；
+/// 安装失败时不留下半安装的目录。
+/// 当前目录的 .enman.lock 锁定了同一版本时，只从锁定的地址下载，哈希不一致则安装失败。
+pub async fn ensure_installed(paths: &EnvManPaths, tool: &str, version: &str) -> Result<bool> {
+    let pin = lock::locked(tool)
+        .filter(|locked| locked.version == version)
+        .map(|locked| locked.artifact());
+    ensure_installed_pinned(paths, tool, version, pin.as_ref()).await
+}
 
-    println!("Installed {} {} successfully!", tool, version);
+/// 与 `ensure_installed` 相同，但使用给定的安装包锁定信息（None 时不校验）
+pub async fn ensure_installed_pinned(paths: &EnvManPaths, tool: &str, version: &str, pin: Option<&Artifact>) -> Result<bool> {
+    let install_path = paths.install_dir(tool).join(version);
+    if install_path.is_dir() {
+        return Ok(false);
+    }
 
-    Ok(())
-}
\ No newline at end of file
+    eprintln!("Installing {} {}", tool, version);
+    if let Err(e) = downloader::install(tool, version, &install_path, pin).await {
+        // 不留下半安装的目录，否则之后会被误认为已安装
+        std::fs::remove_dir_all(&install_path).ok();
+        return Err(e);
+    }
+    shim_manager::create_shims(paths, tool, version)?;
+    Ok(true)
+}
@@ -3,11 +3,13 @@ use clap::Args;
 use anyhow::Result;
 use std::fs;
 use crate::core::paths::EnvManPaths;
+use crate::core::resolve::SourceKind;
+use crate::core::{enmanrc, resolve, settings, version};
 
 #[derive(Args)]
 pub struct ListArgs {
     /// Optional: tool name (e.g., 'node'). If omitted, show current global versions of all tools.
-    #[arg(value_name = "TOOL", help = crate::localization::get_localizer().t("arg_tool_help"))]
+    #[arg(value_name = "TOOL", add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool), help = crate::localization::get_localizer().t("arg_tool_help"))]
     pub tool: Option<String>,
 
     /// List all available tools that can be downloaded
@@ -30,6 +32,10 @@ pub async fn run(args: ListArgs) -> Result<()> {
 
     let paths = EnvManPaths::new()?;
 
+    if !args.available && !args.remote {
+        print_profile();
+    }
+
     if args.available {
         list_available_tools().await
     } else if args.remote {
@@ -136,7 +142,7 @@ async fn list_remote_versions(tool: &str) -> Result<()> {
                     println!("  5.7.x");
                 }
                 _ => {
-                    eprintln!("{} '{}' {}", crate::localization::get_localizer().t("Tool is not supported for remote version listing"), tool, "");
+                    eprintln!("{} '{}'", crate::localization::get_localizer().t("Tool is not supported for remote version listing"), tool);
                 }
             }
         }
@@ -160,81 +166,75 @@ async fn list_available_tools() -> Result<()> {
     Ok(())
 }
 
+/// 选择了配置方案时在列表前注明，项目级版本按该方案解析
+fn print_profile() {
+    let Some(profile) = enmanrc::active_profile() else {
+        return;
+    };
+    println!("Profile: {}", profile);
+    if let Ok(cwd) = std::env::current_dir() {
+        if resolve::layered_config(&cwd).undefined_profile().is_some() {
+            settings::warn(format_args!("profile '{}' is not defined in any {}", profile, enmanrc::FILE_NAME));
+        }
+    }
+}
+
 // === 详细模式：列出某个工具的所有已安装版本 ===
 fn list_tool_versions_detailed(paths: &EnvManPaths, tool: &str) -> Result<()> {
-    let install_dir = paths.install_dir(tool);
-    if !install_dir.exists() {
-        println!("{} {} {}.", crate::localization::get_localizer().t("No versions of"), tool, crate::localization::get_localizer().t("installed"));
-        return Ok(());
-    }
+    let localizer = crate::localization::get_localizer();
 
-    let global_version = fs::read_to_string(paths.global.join(tool))
-        .ok()
-        .map(|s| s.trim().to_string());
+    // 当前目录覆盖全局版本的生效版本（项目文件或环境变量）及其标注
+    let active = resolve::resolve_current(paths, tool)?
+        .and_then(|r| Some((override_label(&r)?, r.version)));
 
-    // 检查本地版本（当前目录下的 .enman-version 文件）
-    let local_version = std::env::current_dir()
-        .ok()
-        .and_then(|dir| {
-            let local_file = dir.join(".enman-version");
-            if local_file.exists() {
-                fs::read_to_string(local_file).ok().map(|s| s.trim().to_string())
-            } else {
-                None
-            }
-        })
-        .and_then(|content| {
-            // 解析本地版本文件内容，格式为 "tool@version"
-            let parts: Vec<&str> = content.split('@').collect();
-            if parts.len() == 2 && parts[0] == tool {
-                Some(parts[1].to_string())
-            } else {
-                None
-            }
-        });
+    let global_version = resolve::global_candidate(paths, tool)
+        .map(|c| resolve::resolve_candidate(paths, tool, c).version);
 
-    let mut versions: Vec<String> = fs::read_dir(install_dir)?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            if entry.file_type().ok()?.is_dir() {
-                Some(entry.file_name().to_string_lossy().into_owned())
-            } else {
-                None
-            }
-        })
-        .collect();
+    let mut versions = paths.installed_versions(tool);
 
     if versions.is_empty() {
-        println!("{} {} {}.", crate::localization::get_localizer().t("No versions of"), tool, crate::localization::get_localizer().t("installed"));
+        println!("{} {} {}.", localizer.t("No versions of"), tool, localizer.t("installed"));
         return Ok(());
     }
 
-    versions.sort();
+    versions.sort_by(|a, b| version::compare_versions(a, b));
 
     for version in &versions {
-        if local_version.as_ref() == Some(version) {
-            println!("->{} ({})", version, crate::localization::get_localizer().t("local"));
+        if let Some((label, _)) = active.as_ref().filter(|(_, active)| active == version) {
+            println!("->{} ({})", version, label);
         } else if global_version.as_ref() == Some(version) {
-            println!("->{} ({})", version, crate::localization::get_localizer().t("global"));
+            println!("->{} ({})", version, localizer.t("global"));
         } else {
             println!("  {}", version);
         }
     }
 
-    // 如果本地版本不同于全局版本且不在安装列表中，也显示它
-    if let Some(local_ver) = &local_version {
-        if global_version.as_ref() != Some(local_ver) && !versions.contains(local_ver) {
-            println!("->{} ({}, {})", local_ver, crate::localization::get_localizer().t("local"), crate::localization::get_localizer().t("not installed"));
+    // 如果生效版本不同于全局版本且不在安装列表中，也显示它
+    if let Some((label, active)) = &active {
+        if global_version.as_ref() != Some(active) && !versions.contains(active) {
+            println!("->{} ({}, {})", active, label, localizer.t("not installed"));
         }
     }
 
     Ok(())
 }
 
+/// 覆盖全局版本的来源在 list 中的标注：项目文件显示 local，环境变量（包括 `enman shell`
+/// 设置的）显示与 `enman why` 相同的来源说明；全局版本没有标注
+fn override_label(resolution: &resolve::Resolution) -> Option<String> {
+    match resolution.source.kind {
+        kind if kind.is_local() => Some(crate::localization::get_localizer().t("local")),
+        SourceKind::EnvVar => Some(resolution.source.to_string()),
+        _ => None,
+    }
+}
+
 // === 摘要模式：列出所有工具的当前全局版本 ===
 fn list_current_global_versions(paths: &EnvManPaths) -> Result<()> {
+    let localizer = crate::localization::get_localizer();
+
     if !paths.global.exists() {
-        println!("{}", crate::localization::get_localizer().t("No global versions set."));
+        println!("{}", localizer.t("No global versions set."));
         return Ok(());
     }
 
@@ -253,34 +253,25 @@ fn list_current_global_versions(paths: &EnvManPaths) -> Result<()> {
         .collect();
 
     if entries.is_empty() {
-        println!("{}", crate::localization::get_localizer().t("No global versions set."));
+        println!("{}", localizer.t("No global versions set."));
         return Ok(());
     }
 
     entries.sort();
 
     for tool in entries {
-        if let Ok(version) = fs::read_to_string(paths.global.join(&tool)) {
-            let version = version.trim();
-            if !version.is_empty() {
-                // 检查当前目录是否有本地版本覆盖
-                if let Ok(current_dir) = std::env::current_dir() {
-                    let local_file = current_dir.join(".enman-version");
-                    if local_file.exists() {
-                        if let Ok(local_content) = fs::read_to_string(&local_file) {
-                            let local_parts: Vec<&str> = local_content.trim().split('@').collect();
-                            if local_parts.len() == 2 && local_parts[0] == tool {
-                                println!("{}: {} ({}: {})", tool, version, crate::localization::get_localizer().t("local"), local_parts[1]);
-                                continue;
-                            }
-                        }
-                    }
-                }
-                
-                println!("{}: {}", tool, version);
-            }
+        let Some(global) = resolve::global_candidate(paths, &tool) else {
+            continue;
+        };
+        let global = resolve::resolve_candidate(paths, &tool, global);
+
+        // 检查当前目录是否有项目文件或环境变量覆盖全局版本
+        let active = resolve::resolve_current(paths, &tool)?;
+        match active.as_ref().and_then(|active| Some((override_label(active)?, &active.version))) {
+            Some((label, version)) => println!("{}: {} ({}: {})", tool, global.version, label, version),
+            None => println!("{}: {}", tool, global.version),
         }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,92 @@
+// src/cli/lock.rs
+use crate::core::lock::{self, LockFile, LockedTool};
+use crate::core::paths::EnvManPaths;
+use crate::core::resolve::{self, SourceKind};
+use crate::core::version;
+use crate::downloader::{self, artifact};
+use crate::cli::install;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use tempfile::TempDir;
+
+#[derive(Args)]
+pub struct LockArgs {}
+
+/// 为当前项目中设置了版本的工具生成 .enman.lock
+///
+/// 只锁定由项目文件（.enman-version、.enmanrc、.nvmrc 等）决定版本的工具，
+/// 全局版本和环境变量覆盖不属于项目，不写入锁文件。
+pub async fn run(_args: LockArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let cwd = std::env::current_dir()?;
+    let lock_path = lock::target(&cwd);
+    let mut lock_file = LockFile::load(&lock_path)?;
+    let previous = lock_file.clone();
+    let platform = lock::current_platform();
+
+    // 当前平台的条目全部重新生成，其他平台的条目保留
+    lock_file.tools.retain(|t| t.platform != platform);
+
+    let mut locked = 0;
+    for tool in resolve::known_tools(&paths) {
+        let Some(candidate) = resolve::candidates(&paths, &tool, &cwd).into_iter().next() else {
+            continue;
+        };
+        if matches!(candidate.source.kind, SourceKind::Global | SourceKind::EnvVar) || version::is_system(&candidate.requested) {
+            continue;
+        }
+
+        let version = version::resolve_unlocked(&paths, &tool, &candidate.requested).await?;
+        let pin = previous
+            .get(&tool)
+            .filter(|entry| entry.version == version)
+            .map(LockedTool::artifact);
+        let artifact = locked_artifact(&paths, &tool, &version, pin.as_ref()).await?;
+
+        println!("🔒 {}@{} ({})", tool, version, candidate.source);
+        lock_file.set(LockedTool {
+            name: tool,
+            version,
+            platform: platform.clone(),
+            url: artifact.url,
+            sha256: artifact.sha256,
+        });
+        locked += 1;
+    }
+
+    if locked == 0 {
+        bail!("No project versions found. Set one with `enman use <tool>@<version>` or in .enmanrc");
+    }
+
+    lock_file.save(&lock_path)?;
+    println!("✅ Wrote {} ({} tools for {})", lock_path.display(), locked, platform);
+    Ok(())
+}
+
+/// 取得某个版本的安装包信息：优先读取安装回执；未安装时通过 `install::ensure_installed_pinned`
+/// 正常安装（同时创建 shim）后读取回执，已安装但没有回执（enman lock 之前安装的）时
+/// 重新下载到临时目录以计算哈希
+async fn locked_artifact(
+    paths: &EnvManPaths,
+    tool: &str,
+    version: &str,
+    pin: Option<&artifact::Artifact>,
+) -> Result<artifact::Artifact> {
+    let install_path = paths.install_dir(tool).join(version);
+    if let Some(receipt) = artifact::read_receipt(&install_path) {
+        return Ok(receipt);
+    }
+
+    if install::ensure_installed_pinned(paths, tool, version, pin).await? {
+        return artifact::read_receipt(&install_path)
+            .with_context(|| format!("Could not determine the download for {}@{}", tool, version));
+    }
+
+    println!("Fetching {}@{} to record its checksum...", tool, version);
+    let temp_dir = TempDir::new()?;
+    let Some(fetched) = downloader::install_artifact(tool, version, &temp_dir.path().join(version), pin).await? else {
+        bail!("Could not determine the download for {}@{}", tool, version);
+    };
+    artifact::write_receipt(&install_path, &fetched)?;
+    Ok(fetched)
+}
@@ -9,6 +9,16 @@ pub mod list;
 pub mod use_cmd; // 注意：文件名是 use_cmd.rs
 pub mod uninstall;
 pub mod config;
+pub mod current;
+pub mod which;
+pub mod why;
+pub mod lock;
+pub mod reshim;
+pub mod activate;
+pub mod exec;
+pub mod shell;
+pub mod completions;
+pub mod run;
 
 /// Top-level CLI parser
 #[derive(Parser)]
@@ -35,7 +45,7 @@ pub enum Commands {
     #[command(alias = "gl", about = crate::tr!("command_global_description"))]
     Global(global::GlobalArgs),
 
-    /// Use a specific version for the current directory
+    /// Set the version for the current directory in .enman-version
     #[command(about = crate::tr!("command_use_description"))]
     Use(use_cmd::UseArgs),
 
@@ -50,6 +60,50 @@ pub enum Commands {
     /// Manage project configuration
     #[command(about = crate::tr!("command_config_description"))]
     Config(config::ConfigArgs),
+
+    /// Show the active version of each tool and where it was set
+    #[command(about = crate::tr!("command_current_description"))]
+    Current(current::CurrentArgs),
+
+    /// Print the absolute path of the binary a tool resolves to
+    #[command(about = crate::tr!("command_which_description"))]
+    Which(which::WhichArgs),
+
+    /// Explain which file chose a tool's version and what it overrides
+    #[command(about = crate::tr!("command_why_description"))]
+    Why(why::WhyArgs),
+
+    /// Record exact versions, download URLs and checksums in .enman.lock
+    #[command(about = crate::tr!("command_lock_description"))]
+    Lock(lock::LockArgs),
+
+    /// Rebuild shims for every installed executable
+    #[command(about = crate::tr!("command_reshim_description"))]
+    Reshim(reshim::ReshimArgs),
+
+    /// Print a shell hook that puts enman on PATH and switches versions on cd
+    #[command(about = crate::tr!("command_activate_description"))]
+    Activate(activate::ActivateArgs),
+
+    /// Run a command with specific tool versions without changing any version files
+    #[command(about = crate::tr!("command_exec_description"))]
+    Exec(exec::ExecArgs),
+
+    /// Set a tool version for the current shell session only
+    #[command(about = crate::tr!("command_shell_description"))]
+    Shell(shell::ShellArgs),
+
+    /// Run tasks from [tasks] in .enmanrc with the project's toolchain
+    #[command(about = crate::tr!("command_run_description"))]
+    Run(run::RunArgs),
+
+    /// Print a shell completion script
+    #[command(about = crate::tr!("command_completions_description"))]
+    Completions(completions::CompletionsArgs),
+
+    /// Print the environment changes for the current directory (used by the activate hook)
+    #[command(hide = true)]
+    HookEnv(activate::HookEnvArgs),
 }
 
 impl Commands {
@@ -61,7 +115,18 @@ impl Commands {
             Self::List(args) => list::run(args).await?,
             Self::Use(args) => use_cmd::run(args).await?,
             Self::Uninstall(args) => uninstall::run(args)?,
-            Self::Config(args) => config::run(args)?,
+            Self::Config(args) => config::run(args).await?,
+            Self::Current(args) => current::run(args)?,
+            Self::Which(args) => which::run(args)?,
+            Self::Why(args) => why::run(args)?,
+            Self::Lock(args) => lock::run(args).await?,
+            Self::Reshim(args) => reshim::run(args)?,
+            Self::Activate(args) => activate::run(args)?,
+            Self::HookEnv(args) => activate::run_hook_env(args)?,
+            Self::Exec(args) => exec::run(args).await?,
+            Self::Shell(args) => shell::run(args)?,
+            Self::Completions(args) => completions::run(args)?,
+            Self::Run(args) => run::run(args).await?,
         }
         Ok(())
     }
@@ -0,0 +1,19 @@
+// src/cli/reshim.rs
+use crate::core::paths::EnvManPaths;
+use crate::core::shim_manager;
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct ReshimArgs {}
+
+pub fn run(_args: ReshimArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let report = shim_manager::reshim(&paths)?;
+
+    println!("Rebuilt {} shims in {}", report.created.len(), paths.shims.display());
+    if !report.removed.is_empty() {
+        println!("Removed shims: {}", report.removed.join(", "));
+    }
+    Ok(())
+}
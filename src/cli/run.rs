@@ -0,0 +1,219 @@
+// src/cli/run.rs
+// 项目任务：执行 .enmanrc `[tasks]` 中的任务。依赖先于任务本身运行，互不依赖的任务并行执行；
+// 每个任务在定义它的 .enmanrc 所在目录中、以该目录生效的工具版本和 `[env]` 运行
+use crate::core::enmanrc::TaskConfig;
+use crate::core::environment;
+use crate::core::paths::EnvManPaths;
+use crate::core::resolve;
+use anyhow::{anyhow, bail, Result};
+use clap::Args;
+use clap_complete::ArgValueCompleter;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Tasks to run (lists the available tasks when omitted)
+    #[arg(add = ArgValueCompleter::new(crate::cli::completions::complete_task))]
+    pub tasks: Vec<String>,
+
+    /// Maximum number of tasks to run at the same time (defaults to the number of CPUs)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+
+    /// Extra arguments appended to the requested tasks' commands, after `--`
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+pub async fn run(args: RunArgs) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let layered = resolve::layered_config(&cwd);
+    let tasks = &layered.config.tasks;
+
+    if args.tasks.is_empty() {
+        if tasks.is_empty() {
+            println!("No tasks defined. Add them to [tasks] in .enmanrc");
+            return Ok(());
+        }
+        let width = tasks.keys().map(String::len).max().unwrap_or(0);
+        for (name, task) in tasks {
+            println!("  {:width$}  {}", name, task.description.as_deref().unwrap_or(&task.run), width = width);
+        }
+        return Ok(());
+    }
+
+    let order = plan(tasks, &args.tasks)?;
+    let paths = EnvManPaths::new()?;
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .max(1);
+
+    // 同一目录中的任务共用一次计算出的环境
+    let mut envs: BTreeMap<PathBuf, (OsString, BTreeMap<String, String>)> = BTreeMap::new();
+    let mut pending = order;
+    let mut done = BTreeSet::new();
+    let mut running = JoinSet::new();
+    let mut failed = None;
+
+    loop {
+        // 出错后不再启动新任务，只等待正在运行的任务结束
+        while failed.is_none() && running.len() < jobs {
+            let Some(index) = pending.iter().position(|name| tasks[name].depends.iter().all(|d| done.contains(d))) else {
+                break;
+            };
+            let name = pending.remove(index);
+            let task = &tasks[&name];
+            let dir = layered
+                .origin("tasks", &name)
+                .and_then(|origin| origin.file.parent())
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| cwd.clone());
+            let (path, vars) = envs.entry(dir.clone()).or_insert_with(|| {
+                let mut tool_env = environment::tool_env(&paths, &dir);
+                tool_env.path.push(paths.shims.clone());
+                (tool_env.path_var(std::env::var_os("PATH").as_deref()), tool_env.vars)
+            });
+
+            let extra: &[String] = if args.tasks.contains(&name) { &args.args } else { &[] };
+            let mut command = shell_command(&task.run, extra);
+            command.current_dir(&dir).envs(vars.iter()).env("PATH", &*path);
+            eprintln!("▶ {}: {}", name, task.run);
+            running.spawn(async move {
+                let started = Instant::now();
+                let status = command.status().await;
+                (name, status, started.elapsed())
+            });
+        }
+
+        let Some(result) = running.join_next().await else {
+            break;
+        };
+        let (name, status, elapsed) = result?;
+        match status {
+            Ok(status) if status.success() => {
+                eprintln!("✅ {} ({:.1}s)", name, elapsed.as_secs_f64());
+                done.insert(name);
+            }
+            Ok(status) => {
+                eprintln!("❌ {} ({})", name, status);
+                failed.get_or_insert_with(|| anyhow!("task '{}' failed ({})", name, status));
+            }
+            Err(e) => {
+                eprintln!("❌ {}", name);
+                failed.get_or_insert_with(|| anyhow!("failed to start task '{}': {}", name, e));
+            }
+        }
+    }
+
+    match failed {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// 请求的任务及其所有依赖，按依赖在前的顺序排列；任务未定义或存在循环依赖时报错
+fn plan(tasks: &BTreeMap<String, TaskConfig>, requested: &[String]) -> Result<Vec<String>> {
+    fn visit(
+        tasks: &BTreeMap<String, TaskConfig>,
+        name: &str,
+        stack: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        if order.iter().any(|done| done == name) {
+            return Ok(());
+        }
+        if let Some(start) = stack.iter().position(|n| n == name) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(name.to_string());
+            bail!("circular task dependency: {}", cycle.join(" -> "));
+        }
+        let Some(task) = tasks.get(name) else {
+            match stack.last() {
+                Some(parent) => bail!("task '{}' depends on unknown task '{}'", parent, name),
+                None => bail!("unknown task '{}'. Run `enman run` to list the available tasks", name),
+            }
+        };
+        stack.push(name.to_string());
+        for dependency in &task.depends {
+            visit(tasks, dependency, stack, order)?;
+        }
+        stack.pop();
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    for name in requested {
+        visit(tasks, name, &mut Vec::new(), &mut order)?;
+    }
+    Ok(order)
+}
+
+/// 通过 shell 执行任务命令，args 追加在命令后面
+fn shell_command(run: &str, args: &[String]) -> Command {
+    #[cfg(windows)]
+    {
+        // 命令行原样交给 cmd：/S 只去掉整行首尾的引号，参数已经按 cmd 的规则加了引号
+        let mut command = Command::new("cmd");
+        let line = std::iter::once(run.to_string()).chain(args.iter().map(|arg| cmd_quote(arg))).collect::<Vec<_>>().join(" ");
+        command.raw_arg(format!("/S /C \"{}\"", line));
+        command
+    }
+    #[cfg(not(windows))]
+    {
+        // 参数通过 "$@" 传入，不需要再转义
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(format!("{} \"$@\"", run)).arg("sh").args(args);
+        command
+    }
+}
+
+/// 按 cmd 的规则给参数加引号：引号中的 & | < > ^ 和空格不会被 cmd 解释，参数中的 " 写成 ""；
+/// % 在引号中也会被展开为环境变量，因此放在引号外用 ^ 转义
+#[cfg(any(windows, test))]
+fn cmd_quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('"', "\"\"").replace('%', "\"^%\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan() {
+        let task = |depends: &[&str]| TaskConfig {
+            run: "true".to_string(),
+            depends: depends.iter().map(|d| d.to_string()).collect(),
+            description: None,
+        };
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut tasks = BTreeMap::from([
+            ("db".to_string(), task(&[])),
+            ("build".to_string(), task(&[])),
+            ("migrate".to_string(), task(&["db"])),
+            ("test".to_string(), task(&["build", "migrate"])),
+        ]);
+
+        assert_eq!(plan(&tasks, &names(&["test"])).unwrap(), names(&["build", "db", "migrate", "test"]));
+        assert_eq!(plan(&tasks, &names(&["migrate", "test"])).unwrap(), names(&["db", "migrate", "build", "test"]));
+        assert!(plan(&tasks, &names(&["deploy"])).is_err());
+
+        tasks.insert("db".to_string(), task(&["test"]));
+        let error = plan(&tasks, &names(&["test"])).unwrap_err().to_string();
+        assert_eq!(error, "circular task dependency: test -> migrate -> db -> test");
+    }
+
+    #[test]
+    fn test_cmd_quote() {
+        assert_eq!(cmd_quote("a b"), "\"a b\"");
+        assert_eq!(cmd_quote("x & del *"), "\"x & del *\"");
+        assert_eq!(cmd_quote("say \"hi\"|more"), "\"say \"\"hi\"\"|more\"");
+        assert_eq!(cmd_quote("%PATH%"), "\"\"^%\"PATH\"^%\"\"");
+    }
+}
@@ -0,0 +1,113 @@
+// src/cli/shell.rs
+// `enman shell tool@version`：只对当前 shell 会话生效的版本
+//
+// enman 作为子进程无法修改父 shell 的环境，因此 `enman activate` 生成的脚本定义了一个
+// 同名的 shell 函数，把 `enman shell` 的输出（ENMAN_<TOOL>_VERSION 的设置语句）交给 shell 执行。
+// 解析器本来就优先使用这些环境变量，关闭 shell 后设置自然失效。
+use crate::cli::activate::{self, Shell};
+use crate::core::environment::EnvChanges;
+use crate::core::paths::EnvManPaths;
+use crate::core::{resolve, settings, version};
+use anyhow::{bail, Result};
+use clap::Args;
+
+/// 当前会话中由 `enman shell` 设置了版本的工具（逗号分隔），供 `--unset` 使用
+pub const SESSION_VAR: &str = "__ENMAN_SESSION";
+
+#[derive(Args)]
+pub struct ShellArgs {
+    /// Tools and versions for this shell session (e.g., "node@16"), or tool names with --unset
+    #[arg(value_name = "TOOL@VERSION", add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool_version))]
+    pub tools: Vec<String>,
+
+    /// Revert the session versions of the given tools (all of them if none are given)
+    #[arg(long)]
+    pub unset: bool,
+
+    /// Shell to emit commands for; passed by the function installed by `enman activate`
+    #[arg(long, value_enum, hide = true)]
+    pub shell: Option<Shell>,
+}
+
+pub fn run(args: ShellArgs) -> Result<()> {
+    let Some(shell) = args.shell else {
+        bail!("`enman shell` needs the shell integration. Add `eval \"$(enman activate bash)\"` (or zsh/fish/powershell) to your shell profile");
+    };
+
+    let mut session: Vec<String> = std::env::var(SESSION_VAR)
+        .unwrap_or_default()
+        .split(',')
+        .filter(|tool| !tool.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut changes = if args.unset {
+        unset_changes(&mut session, &args.tools)
+    } else {
+        if args.tools.is_empty() {
+            bail!("Specify at least one tool@version, e.g. `enman shell node@16`");
+        }
+        let paths = EnvManPaths::new()?;
+        let mut changes = EnvChanges::default();
+        for spec in &args.tools {
+            let (tool, requested) = crate::cli::parse_tool_version(spec).map_err(anyhow::Error::msg)?;
+            if !version::is_system(&requested) && version::resolve_installed(&paths, &tool, &requested).is_none() {
+                settings::warn(format_args!("{}@{} is not installed. Run: enman install {}@{}", tool, requested, tool, requested));
+            }
+            eprintln!("{}@{} set for this shell session", tool, requested);
+            changes.set.push((resolve::env_var_name(&tool), requested));
+            if !session.contains(&tool) {
+                session.push(tool);
+            }
+        }
+        changes
+    };
+
+    if session.is_empty() {
+        changes.unset.push(SESSION_VAR.to_string());
+    } else {
+        changes.set.push((SESSION_VAR.to_string(), session.join(",")));
+    }
+    print!("{}", activate::render(shell, &changes));
+    Ok(())
+}
+
+/// 撤销会话版本；tools 为空时撤销全部
+fn unset_changes(session: &mut Vec<String>, tools: &[String]) -> EnvChanges {
+    // 允许写成 node 或 node@16
+    let tools: Vec<&str> = tools.iter().map(|t| t.split('@').next().unwrap_or(t)).collect();
+    let mut changes = EnvChanges::default();
+    session.retain(|tool| {
+        let revert = tools.is_empty() || tools.contains(&tool.as_str());
+        if revert {
+            changes.unset.push(resolve::env_var_name(tool));
+        }
+        !revert
+    });
+    // 不是由 enman shell 设置的（例如用户自己 export 的）也一并撤销
+    for tool in tools {
+        let name = resolve::env_var_name(tool);
+        if !changes.unset.contains(&name) {
+            changes.unset.push(name);
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unset_changes() {
+        let mut session = vec!["node".to_string(), "java".to_string()];
+        let changes = unset_changes(&mut session, &["node@16".to_string()]);
+        assert_eq!(changes.unset, vec!["ENMAN_NODE_VERSION"]);
+        assert_eq!(session, vec!["java"]);
+
+        let mut session = vec!["node".to_string(), "java".to_string()];
+        let changes = unset_changes(&mut session, &[]);
+        assert_eq!(changes.unset, vec!["ENMAN_NODE_VERSION", "ENMAN_JAVA_VERSION"]);
+        assert!(session.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+// src/cli/which.rs
+use crate::core::paths::EnvManPaths;
+use crate::core::shim;
+use anyhow::{bail, Result};
+use clap::Args;
+
+#[derive(Args)]
+pub struct WhichArgs {
+    /// Tool or executable name (e.g., 'node', 'npm', 'javac')
+    #[arg(value_name = "TOOL", help = crate::tr!("arg_tool_help"))]
+    pub tool: String,
+}
+
+pub fn run(args: WhichArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let exe = args.tool;
+
+    let Some(resolution) = shim::resolve_executable(&paths, &exe)? else {
+        // 没有设置版本时 shim 会回退到系统中的可执行文件
+        if let Some(binary) = shim::system_executable(&paths, &exe) {
+            println!("{}", binary.display());
+            return Ok(());
+        }
+        let Some(tool) = shim::owning_tools(&paths, &exe).into_iter().next() else {
+            bail!("No installed tool provides '{}'", exe);
+        };
+        bail!("No version of {} is set. Run: enman global {}@<version>", tool, tool);
+    };
+
+    if resolution.is_system() {
+        match shim::system_executable(&paths, &exe) {
+            Some(binary) => println!("{}", binary.display()),
+            None => bail!("{} is set to 'system' by {}, but no system '{}' was found on PATH", resolution.tool, resolution.source, exe),
+        }
+        return Ok(());
+    }
+
+    let tool = &resolution.tool;
+    let binary = resolution.binary(&exe);
+    if !resolution.installed {
+        bail!(
+            "{}@{} is not installed (set by {}). Run: enman install {}@{}",
+            tool, resolution.requested, resolution.source, tool, resolution.requested
+        );
+    }
+    if !binary.exists() {
+        bail!("{}@{} does not provide '{}' (set by {})", tool, resolution.version, exe, resolution.source);
+    }
+
+    println!("{}", binary.display());
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// src/cli/why.rs
+use crate::core::paths::EnvManPaths;
+use crate::core::{resolve, shim};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args)]
+pub struct WhyArgs {
+    /// Tool name (e.g., 'node')
+    #[arg(value_name = "TOOL", add = clap_complete::ArgValueCompleter::new(crate::cli::completions::complete_tool), help = crate::tr!("arg_tool_help"))]
+    pub tool: String,
+}
+
+pub fn run(args: WhyArgs) -> Result<()> {
+    let paths = EnvManPaths::new()?;
+    let tool = args.tool;
+    let cwd = std::env::current_dir()?;
+
+    let mut candidates = resolve::candidates(&paths, &tool, &cwd).into_iter();
+    let Some(winner) = candidates.next() else {
+        println!("{}: {}", tool, crate::tr!("No version set"));
+        println!("Run: enman global {}@<version>", tool);
+        return Ok(());
+    };
+
+    let resolution = resolve::resolve_candidate(&paths, &tool, winner);
+    if resolution.requested == resolution.version {
+        println!("{} {}", tool, resolution.version);
+    } else {
+        println!("{} {} (requested \"{}\")", tool, resolution.version, resolution.requested);
+    }
+    println!("  set by: {}", resolution.source);
+    if resolution.is_system() {
+        match shim::system_executable(&paths, &tool) {
+            Some(binary) => println!("  binary: {} (system)", binary.display()),
+            None => println!("  binary: {}", crate::tr!("not found on PATH")),
+        }
+    } else if resolution.installed {
+        println!("  binary: {}", resolution.binary(&tool).display());
+    } else {
+        println!("  binary: {} ({})", resolution.binary(&tool).display(), crate::tr!("not installed"));
+    }
+
+    let overridden: Vec<_> = candidates.collect();
+    if !overridden.is_empty() {
+        println!("  overrides:");
+        for candidate in overridden {
+            println!("    {:<12} {}", candidate.requested, candidate.source);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,198 @@
+// src/core/ecosystem.rs
+// 读取其他版本管理器（nvm、pyenv、jenv、SDKMAN!、asdf）的版本文件，
+// 让 enman 无需迁移即可在已有项目中工作
+use std::path::{Path, PathBuf};
+
+/// 支持的版本文件，按同一目录内的优先级从高到低排列
+pub const ECOSYSTEM_FILES: &[&str] = &[
+    ".tool-versions",
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    ".java-version",
+    ".sdkmanrc",
+];
+
+/// 从版本文件中读取到的版本
+#[derive(Debug, Clone)]
+pub struct EcosystemVersion {
+    pub file: PathBuf,
+    /// 版本所在的行号（从 1 开始）
+    pub line: usize,
+    pub version: String,
+}
+
+/// 在单个目录中按优先级查找工具的版本
+pub fn find_in_dir(dir: &Path, tool: &str) -> Option<EcosystemVersion> {
+    ECOSYSTEM_FILES.iter().find_map(|name| {
+        let file = dir.join(name);
+        if !file.is_file() {
+            return None;
+        }
+        let content = std::fs::read_to_string(&file).ok()?;
+        let (line, version) = parse(name, &content, tool)?;
+        Some(EcosystemVersion { file, line, version })
+    })
+}
+
+/// 解析版本文件内容，返回 (行号, 版本)
+pub fn parse(file_name: &str, content: &str, tool: &str) -> Option<(usize, String)> {
+    match file_name {
+        ".tool-versions" => parse_tool_versions(content, tool),
+        ".nvmrc" | ".node-version" if tool == "node" => {
+            first_line(content).and_then(|(n, v)| normalize_node(v).map(|v| (n, v)))
+        }
+        ".python-version" if tool == "python" => {
+            first_line(content).and_then(|(n, v)| normalize_python(v).map(|v| (n, v)))
+        }
+        ".java-version" if tool == "java" => {
+            first_line(content).and_then(|(n, v)| normalize_java(v).map(|v| (n, v)))
+        }
+        ".sdkmanrc" if tool == "java" => parse_sdkmanrc(content),
+        _ => None,
+    }
+}
+
+/// 第一个非空、非注释行
+fn first_line(content: &str) -> Option<(usize, &str)> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .find(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// asdf 的 .tool-versions：每行 `<plugin> <version> [<fallback>...]`
+fn parse_tool_versions(content: &str, tool: &str) -> Option<(usize, String)> {
+    for (i, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut fields = line.split_whitespace();
+        let (Some(plugin), Some(version)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if asdf_plugin_tool(plugin) != Some(tool) {
+            continue;
+        }
+        let version = match tool {
+            "node" => normalize_node(version),
+            "python" => normalize_python(version),
+            "java" => normalize_java(version),
+            _ => normalize_plain(version),
+        };
+        return version.map(|v| (i + 1, v));
+    }
+    None
+}
+
+fn asdf_plugin_tool(plugin: &str) -> Option<&'static str> {
+    match plugin {
+        "nodejs" | "node" => Some("node"),
+        "python" => Some("python"),
+        "java" => Some("java"),
+        "redis" => Some("redis"),
+        "mysql" => Some("mysql"),
+        "mariadb" => Some("mariadb"),
+        _ => None,
+    }
+}
+
+/// SDKMAN! 的 .sdkmanrc：`java=17.0.8-tem`
+fn parse_sdkmanrc(content: &str) -> Option<(usize, String)> {
+    content.lines().enumerate().find_map(|(i, line)| {
+        let (key, value) = line.trim().split_once('=')?;
+        if key.trim() != "java" {
+            return None;
+        }
+        normalize_java(value.trim()).map(|v| (i + 1, v))
+    })
+}
+
+/// 普通版本号：必须以数字开头（排除 ref:xxx、path:xxx 等）；system 表示使用系统中的版本
+fn normalize_plain(version: &str) -> Option<String> {
+    if version == crate::core::version::SYSTEM {
+        return Some(version.to_string());
+    }
+    let version = version.strip_prefix('v').unwrap_or(version);
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| version.to_string())
+}
+
+/// nvm 风格：v18.17.0、18、lts/*、lts/hydrogen、node
+fn normalize_node(version: &str) -> Option<String> {
+    let version = version.trim();
+    if let Some(codename) = version.strip_prefix("lts/") {
+        if codename == "*" {
+            return Some("lts".to_string());
+        }
+        return node_lts_codename_major(codename).map(|major| major.to_string());
+    }
+    match version {
+        "node" | "stable" | "latest" => Some("latest".to_string()),
+        "lts" => Some("lts".to_string()),
+        _ => normalize_plain(version),
+    }
+}
+
+fn node_lts_codename_major(codename: &str) -> Option<u32> {
+    let major = match codename.to_lowercase().as_str() {
+        "argon" => 4,
+        "boron" => 6,
+        "carbon" => 8,
+        "dubnium" => 10,
+        "erbium" => 12,
+        "fermium" => 14,
+        "gallium" => 16,
+        "hydrogen" => 18,
+        "iron" => 20,
+        "jod" => 22,
+        "krypton" => 24,
+        _ => return None,
+    };
+    Some(major)
+}
+
+/// pyenv 风格：3.11.5、3.11；pypy、miniconda 等发行版不受支持
+fn normalize_python(version: &str) -> Option<String> {
+    normalize_plain(version)
+}
+
+/// Java 版本统一为特性版本号，与 enman 的 Java 安装目录一致：
+/// 17.0.8 → 17，1.8 → 8，temurin-17.0.8+7 → 17，17.0.8-tem → 17
+fn normalize_java(version: &str) -> Option<String> {
+    if version == crate::core::version::SYSTEM {
+        return Some(version.to_string());
+    }
+    let start = version.find(|c: char| c.is_ascii_digit())?;
+    let numeric: String = version[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let mut parts = numeric.split('.').filter(|p| !p.is_empty());
+    let major = parts.next()?;
+    let feature = if major == "1" { parts.next()? } else { major };
+    Some(feature.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ecosystem_files() {
+        assert_eq!(parse(".nvmrc", "v18.17.0\n", "node"), Some((1, "18.17.0".to_string())));
+        assert_eq!(parse(".nvmrc", "lts/hydrogen", "node"), Some((1, "18".to_string())));
+        assert_eq!(parse(".nvmrc", "lts/*", "node"), Some((1, "lts".to_string())));
+        assert_eq!(parse(".nvmrc", "18", "python"), None);
+        assert_eq!(parse(".python-version", "# pinned\n3.11.5\n", "python"), Some((2, "3.11.5".to_string())));
+        assert_eq!(parse(".python-version", "pypy3.9", "python"), None);
+        assert_eq!(parse(".java-version", "1.8", "java"), Some((1, "8".to_string())));
+        assert_eq!(parse(".sdkmanrc", "maven=3.9.4\njava=17.0.8-tem\n", "java"), Some((2, "17".to_string())));
+
+        let tool_versions = "# asdf\nnodejs 20.11.0 18.19.1\njava temurin-21.0.2+13.0.LTS\npython system\n";
+        assert_eq!(parse(".tool-versions", tool_versions, "node"), Some((2, "20.11.0".to_string())));
+        assert_eq!(parse(".tool-versions", tool_versions, "java"), Some((3, "21".to_string())));
+        assert_eq!(parse(".tool-versions", tool_versions, "python"), Some((4, "system".to_string())));
+        assert_eq!(parse(".nvmrc", "system\n", "node"), Some((1, "system".to_string())));
+    }
+}
@@ -0,0 +1,870 @@
+// src/core/enmanrc.rs
+// 项目配置文件 .enmanrc 的唯一解析入口
+//
+// 结构由 serde 定义：顶层的 `root = true`、`env_files` 和 `[tools]`、`[env]`、`[services]`、`[tasks]`、`[settings]`、
+// `[profiles.<name>]`，
+// 另外兼容两种旧写法：顶层的 `node = "18"`，以及无法按 TOML 解析的 `node=18.17.0` 逐行写法。
+// 值都带有在文件中的位置（toml::Spanned），`enman config validate` 用它报告行号和列号。
+// 各节分别解析：`[settings]` 中的拼写错误只让这一节被跳过，`[tools]` 中的版本仍然生效。
+//
+// monorepo 中每一层目录都可以有 .enmanrc，`LayeredConfig` 从最外层到当前目录逐层合并，
+// 设置了 `root = true` 的文件是最外层，不再继续向上查找。
+//
+// `[profiles.ci]` 这样的配置方案可以覆盖 `[tools]`、`[env]` 和 `[services]`，由 ENMAN_PROFILE
+// （或命令行的 --profile）选择。各层的方案在所有层的普通配置合并之后才应用，
+// 因此最外层 .enmanrc 中的方案同样覆盖内层的 `[tools]`（见 `LayeredConfig::merge`）。
+use anyhow::{Context, Result};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use toml::Spanned;
+
+use crate::core::version;
+use crate::downloader;
+
+pub const FILE_NAME: &str = ".enmanrc";
+
+/// 顶层中有特殊含义的键，其余顶层键按旧写法视为工具
+const SECTIONS: &[&str] = &["root", "env_files", "tools", "env", "services", "tasks", "settings", "profiles"];
+
+/// 选择配置方案的环境变量
+pub const PROFILE_VAR: &str = "ENMAN_PROFILE";
+
+/// 当前选择的配置方案
+pub fn active_profile() -> Option<String> {
+    profile_name(std::env::var(PROFILE_VAR).ok())
+}
+
+/// ENMAN_PROFILE 的值对应的方案名，空值表示没有选择方案
+pub fn profile_name(value: Option<String>) -> Option<String> {
+    value.map(|p| p.trim().to_string()).filter(|p| !p.is_empty())
+}
+
+/// .enmanrc 的结构，每个字段对应顶层的一节（见 `Document::parse`）
+#[derive(Debug, Default)]
+struct Schema {
+    root: bool,
+    env_files: Vec<Spanned<String>>,
+    tools: BTreeMap<Spanned<String>, Spanned<VersionValue>>,
+    env: BTreeMap<Spanned<String>, Spanned<String>>,
+    services: BTreeMap<Spanned<String>, Spanned<ServiceValue>>,
+    tasks: BTreeMap<Spanned<String>, Spanned<TaskValue>>,
+    settings: Settings,
+    profiles: BTreeMap<String, Profile>,
+}
+
+/// `[profiles.<name>]`：选中时覆盖顶层的同名项
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Profile {
+    tools: BTreeMap<Spanned<String>, Spanned<VersionValue>>,
+    env: BTreeMap<Spanned<String>, Spanned<String>>,
+    services: BTreeMap<Spanned<String>, Spanned<ServiceValue>>,
+}
+
+/// `[settings]`
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// shim 遇到未安装的版本时自动安装
+    pub auto_install: Option<bool>,
+}
+
+impl Settings {
+    /// 用 other 中设置了的项覆盖当前设置，返回被覆盖的设置名
+    fn merge(&mut self, other: &Settings) -> Vec<&'static str> {
+        let mut merged = Vec::new();
+        if other.auto_install.is_some() {
+            self.auto_install = other.auto_install;
+            merged.push("auto_install");
+        }
+        merged
+    }
+
+    /// 所有设置了的项，(设置名, 值)
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        self.auto_install.map(|v| ("auto_install", v.to_string())).into_iter().collect()
+    }
+}
+
+/// 工具版本：字符串，或 `java = 17` 这样的整数
+#[derive(Debug)]
+struct VersionValue(String);
+
+impl<'de> Deserialize<'de> for VersionValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct VersionVisitor;
+
+        impl Visitor<'_> for VersionVisitor {
+            type Value = VersionValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a version string such as \"18\" or \"3.11.5\"")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+                Ok(VersionValue(v.trim().to_string()))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> std::result::Result<Self::Value, E> {
+                Ok(VersionValue(v.to_string()))
+            }
+        }
+
+        deserializer.deserialize_any(VersionVisitor)
+    }
+}
+
+/// `[services]` 中的一项：`mysql = true` 或 `redis = { port = 6380 }`
+#[derive(Debug)]
+enum ServiceValue {
+    Enabled(bool),
+    Options(ServiceOptions),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServiceOptions {
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+    port: Option<u16>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl<'de> Deserialize<'de> for ServiceValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct ServiceVisitor;
+
+        impl<'de> Visitor<'de> for ServiceVisitor {
+            type Value = ServiceValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("true/false or a table such as { port = 3306 }")
+            }
+
+            fn visit_bool<E: de::Error>(self, v: bool) -> std::result::Result<Self::Value, E> {
+                Ok(ServiceValue::Enabled(v))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Self::Value, A::Error> {
+                ServiceOptions::deserialize(de::value::MapAccessDeserializer::new(map)).map(ServiceValue::Options)
+            }
+        }
+
+        deserializer.deserialize_any(ServiceVisitor)
+    }
+}
+
+/// `[tasks]` 中的一项：`test = "npm test"` 或 `migrate = { run = "...", depends = ["db"] }`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskConfig {
+    /// 通过 shell 执行的命令
+    pub run: String,
+    /// 需要先成功完成的任务
+    #[serde(default)]
+    pub depends: Vec<String>,
+    /// `enman run` 列出任务时显示的说明
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug)]
+struct TaskValue(TaskConfig);
+
+impl<'de> Deserialize<'de> for TaskValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct TaskVisitor;
+
+        impl<'de> Visitor<'de> for TaskVisitor {
+            type Value = TaskValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a command string or a table such as { run = \"npm test\", depends = [\"build\"] }")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<Self::Value, E> {
+                Ok(TaskValue(TaskConfig { run: v.to_string(), ..TaskConfig::default() }))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<Self::Value, A::Error> {
+                TaskConfig::deserialize(de::value::MapAccessDeserializer::new(map)).map(TaskValue)
+            }
+        }
+
+        deserializer.deserialize_any(TaskVisitor)
+    }
+}
+
+/// 解析后的 .enmanrc
+#[derive(Debug, Default, PartialEq)]
+pub struct ProjectConfig {
+    /// 工具 → 版本要求
+    pub tools: BTreeMap<String, String>,
+    /// `[env]` 中的环境变量，值中可以有 `{{tools.java.home}}` 这样的引用（见 `parse_template`）
+    pub env: BTreeMap<String, String>,
+    /// `env_files`：需要读取的 .env 文件，相对于 .enmanrc 所在的目录
+    pub env_files: Vec<String>,
+    /// 需要启动的服务（已禁用的不在其中）
+    pub services: BTreeMap<String, ServiceConfig>,
+    /// 设置为 false 的服务，合并时用来关闭外层声明的服务
+    pub disabled_services: BTreeSet<String>,
+    /// `[tasks]`：`enman run` 执行的任务
+    pub tasks: BTreeMap<String, TaskConfig>,
+    pub settings: Settings,
+    /// `root = true`：不再向上查找其他 .enmanrc
+    pub root: bool,
+    /// 文件中定义的配置方案
+    pub profiles: BTreeSet<String>,
+    /// 选中的配置方案在本文件中的覆盖项，只设置了 tools、env、services 和 disabled_services
+    pub profile: Option<Box<ProjectConfig>>,
+    /// 无法解析、已被跳过的节（如 "settings"），其余各节照常生效
+    pub invalid_sections: Vec<&'static str>,
+    /// 工具版本所在的行号（从 1 开始）
+    tool_lines: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ServiceConfig {
+    pub port: Option<u16>,
+}
+
+impl ProjectConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::load_with_profile(path, active_profile().as_deref())
+    }
+
+    pub fn load_with_profile(path: &Path, profile: Option<&str>) -> Result<Self> {
+        let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse_with_profile(&content, profile).with_context(|| format!("Invalid configuration in {}. Run `enman config validate` for details", path.display()))
+    }
+
+    /// 解析配置；只在 TOML 语法错误时失败，无法解析的节记录在 `invalid_sections` 中，
+    /// 未知工具等问题由 `validate` 报告。指定方案的覆盖项放在 `profile` 中，不改动顶层配置
+    /// （文件中没有该方案时为 None）
+    pub fn parse_with_profile(content: &str, profile: Option<&str>) -> Result<Self> {
+        let document = match Document::parse(content) {
+            Ok(document) => document,
+            Err(e) => match parse_legacy_lines(content) {
+                Some(config) => return Ok(config),
+                None => return Err(e.into()),
+            },
+        };
+
+        let mut config = ProjectConfig::default();
+        let schema = &document.schema;
+        // 同时出现时 [tools] 中的条目在后，覆盖顶层的旧写法
+        let tools = document.legacy_tools.iter().map(|(k, v)| (k, v)).chain(schema.tools.iter());
+        config.add_tables(content, tools, &schema.env, &schema.services);
+        if let Some(selected) = schema.profiles.iter().find(|(name, _)| Some(name.as_str()) == profile) {
+            let mut overrides = ProjectConfig::default();
+            overrides.add_tables(content, selected.1.tools.iter(), &selected.1.env, &selected.1.services);
+            config.profile = Some(Box::new(overrides));
+        }
+        config.profiles = schema.profiles.keys().cloned().collect();
+
+        for (name, task) in document.schema.tasks {
+            config.tasks.insert(name.into_inner(), task.into_inner().0);
+        }
+        config.env_files = document.schema.env_files.into_iter().map(Spanned::into_inner).collect();
+        config.settings = document.schema.settings;
+        config.root = document.schema.root;
+        config.invalid_sections = document.invalid.iter().map(|(name, _)| *name).collect();
+        Ok(config)
+    }
+
+    /// 加入一组 `[tools]`、`[env]`、`[services]`，覆盖已有的同名项
+    fn add_tables<'a>(
+        &mut self,
+        content: &str,
+        tools: impl Iterator<Item = (&'a Spanned<String>, &'a Spanned<VersionValue>)>,
+        env: &BTreeMap<Spanned<String>, Spanned<String>>,
+        services: &BTreeMap<Spanned<String>, Spanned<ServiceValue>>,
+    ) {
+        for (tool, value) in tools {
+            self.tools.insert(tool.get_ref().clone(), value.get_ref().0.clone());
+            self.tool_lines.insert(tool.get_ref().clone(), position(content, tool.span().start).0);
+        }
+        for (name, value) in env {
+            self.env.insert(name.get_ref().clone(), value.get_ref().clone());
+        }
+        for (name, value) in services {
+            let name = name.get_ref().clone();
+            let service = match value.get_ref() {
+                ServiceValue::Enabled(true) => ServiceConfig::default(),
+                ServiceValue::Options(options) if options.enabled => ServiceConfig { port: options.port },
+                _ => {
+                    self.services.remove(&name);
+                    self.disabled_services.insert(name);
+                    continue;
+                }
+            };
+            self.disabled_services.remove(&name);
+            self.services.insert(name, service);
+        }
+    }
+
+    /// 工具版本在文件中的行号
+    pub fn tool_line(&self, tool: &str) -> Option<usize> {
+        self.tool_lines.get(tool).copied()
+    }
+}
+
+/// 合并后的值来自哪个文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Origin {
+    pub file: PathBuf,
+    /// 只有工具版本记录了行号
+    pub line: Option<usize>,
+}
+
+/// 从最外层到当前目录逐层合并的 .enmanrc：内层的工具版本、环境变量、服务和设置覆盖外层的同名项
+#[derive(Debug, Default)]
+pub struct LayeredConfig {
+    /// 参与合并的文件，从最外层到最内层
+    pub files: Vec<PathBuf>,
+    pub config: ProjectConfig,
+    /// 所有层的 env_files（已转换为绝对路径），外层在前，内层文件中的变量覆盖外层
+    pub env_files: Vec<PathBuf>,
+    /// "tools.node"、"env.APP_ENV"、"services.mysql"、"tasks.test"、"settings.auto_install" → 来源
+    origins: BTreeMap<String, Origin>,
+}
+
+impl LayeredConfig {
+    /// layers 按从外到内的顺序排列
+    ///
+    /// 先合并所有层的普通配置，再按同样的顺序应用各层选中的配置方案：
+    /// 方案中的项覆盖任何一层的普通配置，多层都定义了同一项时内层的方案生效。
+    pub fn merge(layers: Vec<(PathBuf, ProjectConfig)>) -> Self {
+        let mut layered = LayeredConfig::default();
+        for (file, layer) in &layers {
+            layered.add_tables(file, layer);
+        }
+        for (file, layer) in &layers {
+            if let Some(profile) = &layer.profile {
+                layered.add_tables(file, profile);
+            }
+        }
+        for (file, layer) in layers {
+            let origin = |line| Origin { file: file.clone(), line };
+            for (name, task) in &layer.tasks {
+                layered.config.tasks.insert(name.clone(), task.clone());
+                layered.origins.insert(format!("tasks.{}", name), origin(None));
+            }
+            for setting in layered.config.settings.merge(&layer.settings) {
+                layered.origins.insert(format!("settings.{}", setting), origin(None));
+            }
+            layered.config.profiles.extend(layer.profiles.iter().cloned());
+            let dir = file.parent().unwrap_or(Path::new(""));
+            layered.env_files.extend(layer.env_files.iter().map(|env_file| dir.join(env_file)));
+            layered.files.push(file);
+        }
+        layered
+    }
+
+    /// 合并一层的 `[tools]`、`[env]`、`[services]`，覆盖已有的同名项
+    fn add_tables(&mut self, file: &Path, layer: &ProjectConfig) {
+        let origin = |line| Origin { file: file.to_path_buf(), line };
+        for (tool, version) in &layer.tools {
+            self.config.tools.insert(tool.clone(), version.clone());
+            self.origins.insert(format!("tools.{}", tool), origin(layer.tool_line(tool)));
+        }
+        for (name, value) in &layer.env {
+            self.config.env.insert(name.clone(), value.clone());
+            self.origins.insert(format!("env.{}", name), origin(None));
+        }
+        for name in &layer.disabled_services {
+            self.config.services.remove(name);
+            self.origins.remove(&format!("services.{}", name));
+        }
+        for (name, service) in &layer.services {
+            self.config.services.insert(name.clone(), service.clone());
+            self.origins.insert(format!("services.{}", name), origin(None));
+        }
+    }
+
+    /// 选择了配置方案、但参与合并的文件都没有定义它时返回方案名
+    pub fn undefined_profile(&self) -> Option<String> {
+        active_profile().filter(|profile| !self.config.profiles.contains(profile))
+    }
+
+    /// 合并结果中某一项的来源，如 `origin("tools", "node")`
+    pub fn origin(&self, section: &str, key: &str) -> Option<&Origin> {
+        self.origins.get(&format!("{}.{}", section, key))
+    }
+}
+
+/// `[env]` 值中 `{{...}}` 引用的内容
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reference {
+    /// `tools.<tool>.home`、`tools.<tool>.version`、`tools.<tool>.bin`
+    Tool { tool: String, field: ToolField },
+    /// `services.<service>.port`
+    ServicePort(String),
+    /// `env.<NAME>`：.env 文件或当前进程中的环境变量
+    Env(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToolField {
+    Home,
+    Version,
+    Bin,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplatePart {
+    Text(String),
+    Reference(Reference),
+}
+
+/// 把 `[env]` 中的值拆成文本和引用，引用写作 `{{tools.java.home}}`，花括号内可以有空格
+pub fn parse_template(value: &str) -> std::result::Result<Vec<TemplatePart>, String> {
+    let mut parts = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(TemplatePart::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..].find("}}").ok_or_else(|| format!("unclosed '{{{{' in \"{}\"", value))?;
+        let expr = rest[start + 2..start + end].trim();
+        parts.push(TemplatePart::Reference(parse_reference(expr)?));
+        rest = &rest[start + end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest.to_string()));
+    }
+    Ok(parts)
+}
+
+fn parse_reference(expr: &str) -> std::result::Result<Reference, String> {
+    let segments: Vec<&str> = expr.split('.').collect();
+    match segments.as_slice() {
+        ["tools", tool, field] => {
+            if !downloader::is_supported(tool) {
+                return Err(unknown_tool(tool));
+            }
+            let field = match *field {
+                "home" => ToolField::Home,
+                "version" => ToolField::Version,
+                "bin" => ToolField::Bin,
+                _ => return Err(format!("unknown field '{}' in '{{{{{}}}}}' (use home, version or bin)", field, expr)),
+            };
+            Ok(Reference::Tool { tool: tool.to_string(), field })
+        }
+        ["services", service, "port"] => {
+            if !downloader::SERVICES.contains(service) {
+                return Err(format!("unknown service '{}' (supported: {})", service, downloader::SERVICES.join(", ")));
+            }
+            Ok(Reference::ServicePort(service.to_string()))
+        }
+        ["env", name] if !name.is_empty() => Ok(Reference::Env(name.to_string())),
+        _ => Err(format!(
+            "cannot resolve '{{{{{}}}}}'; use tools.<tool>.home, tools.<tool>.version, tools.<tool>.bin, services.<service>.port or env.<NAME>",
+            expr
+        )),
+    }
+}
+
+/// 一次 TOML 解析的结果：serde 结构，加上顶层的旧写法工具、不支持的键和无法解析的节
+struct Document {
+    schema: Schema,
+    legacy_tools: Vec<(Spanned<String>, Spanned<VersionValue>)>,
+    unsupported: Vec<(Spanned<String>, &'static str)>,
+    invalid: Vec<(&'static str, toml::de::Error)>,
+}
+
+impl Document {
+    /// 只在 TOML 语法错误时失败；类型错误或未知的键只让所在的节被跳过，记录在 `invalid` 中
+    fn parse(content: &str) -> std::result::Result<Self, toml::de::Error> {
+        // 顶层单独解析一次，找出 SECTIONS 之外的键（值不能用 Spanned<toml::Value>，
+        // toml 无法把 `[profiles.ci]` 这样的多级表头解析成带位置的值）
+        let top: BTreeMap<Spanned<String>, toml::Value> = toml::from_str(content)?;
+
+        let mut invalid = Vec::new();
+        let schema = Schema {
+            root: section(content, "root", &mut invalid),
+            env_files: section(content, "env_files", &mut invalid),
+            tools: section(content, "tools", &mut invalid),
+            env: section(content, "env", &mut invalid),
+            services: section(content, "services", &mut invalid),
+            tasks: section(content, "tasks", &mut invalid),
+            settings: section(content, "settings", &mut invalid),
+            profiles: section(content, "profiles", &mut invalid),
+        };
+
+        let mut legacy_tools = Vec::new();
+        let mut unsupported = Vec::new();
+        for (key, value) in top {
+            if SECTIONS.contains(&key.get_ref().as_str()) {
+                continue;
+            }
+            let span = value_span(content, key.span().end);
+            match value {
+                toml::Value::String(s) => legacy_tools.push((key, Spanned::new(span, VersionValue(s.trim().to_string())))),
+                toml::Value::Integer(i) => legacy_tools.push((key, Spanned::new(span, VersionValue(i.to_string())))),
+                toml::Value::Table(_) => unsupported.push((key, "unsupported section")),
+                _ => unsupported.push((key, "unsupported key")),
+            }
+        }
+        Ok(Document { schema, legacy_tools, unsupported, invalid })
+    }
+}
+
+/// 单独解析顶层的一节，失败时记录错误并使用默认值
+fn section<T: DeserializeOwned + Default>(
+    content: &str,
+    name: &'static str,
+    invalid: &mut Vec<(&'static str, toml::de::Error)>,
+) -> T {
+    let seed = Section { name, marker: PhantomData };
+    seed.deserialize(toml::Deserializer::new(content)).unwrap_or_else(|e| {
+        invalid.push((name, e));
+        T::default()
+    })
+}
+
+/// 只读取顶层中名为 name 的一节，其余键跳过
+struct Section<T> {
+    name: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned + Default> DeserializeSeed<'de> for Section<T> {
+    type Value = T;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<T, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned + Default> Visitor<'de> for Section<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a table")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<T, A::Error> {
+        let mut value = T::default();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.name {
+                value = map.next_value()?;
+            } else {
+                map.next_value::<de::IgnoredAny>()?;
+            }
+        }
+        Ok(value)
+    }
+}
+
+/// 顶层 `key = value` 中值的位置，key_end 为键结束的位置
+fn value_span(content: &str, key_end: usize) -> std::ops::Range<usize> {
+    let rest = &content[key_end..];
+    let start = rest.find('=').map(|i| i + 1).unwrap_or(0);
+    let start = key_end + start + (rest[start..].len() - rest[start..].trim_start().len());
+    let end = content[start..].find('\n').map(|i| start + i).unwrap_or(content.len());
+    start..end
+}
+
+/// 旧的逐行写法：`node=18.17.0`（值不带引号，不是合法的 TOML）
+fn parse_legacy_lines(content: &str) -> Option<ProjectConfig> {
+    let mut config = ProjectConfig::default();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        // 跳过空行和注释
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line.split_once('=')?;
+        let (key, value) = (key.trim(), value.trim().trim_matches('"'));
+        if key.is_empty() || key.contains(char::is_whitespace) || key.starts_with('[') || SECTIONS.contains(&key) {
+            return None;
+        }
+        if !value.is_empty() {
+            config.tools.insert(key.to_string(), value.to_string());
+            config.tool_lines.insert(key.to_string(), i + 1);
+        }
+    }
+    Some(config)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// `enman config validate` 报告的一个问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 行号和列号都从 1 开始
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(content: &str, offset: usize, message: String) -> Self {
+        let (line, column) = position(content, offset);
+        Diagnostic { severity: Severity::Error, line, column, message }
+    }
+}
+
+/// 检查配置内容，返回所有发现的问题（按位置排序）
+pub fn validate(content: &str) -> Vec<Diagnostic> {
+    let document = match Document::parse(content) {
+        Ok(document) => document,
+        Err(e) => {
+            let offset = e.span().map(|span| span.start).unwrap_or(0);
+            if let Some(config) = parse_legacy_lines(content) {
+                let mut diagnostics = vec![Diagnostic {
+                    severity: Severity::Warning,
+                    line: 1,
+                    column: 1,
+                    message: "legacy key=value format; quote the versions and put them under [tools]".to_string(),
+                }];
+                for (tool, requested) in &config.tools {
+                    let line = config.tool_line(tool).unwrap_or(1);
+                    if let Err(message) = check_tool(tool, requested) {
+                        diagnostics.push(Diagnostic { severity: Severity::Error, line, column: 1, message });
+                    }
+                }
+                return diagnostics;
+            }
+            return vec![Diagnostic::error(content, offset, toml_message(&e))];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    for (_, e) in &document.invalid {
+        let offset = e.span().map(|span| span.start).unwrap_or(0);
+        diagnostics.push(Diagnostic::error(content, offset, toml_message(e)));
+    }
+    for (key, message) in &document.unsupported {
+        diagnostics.push(Diagnostic::error(content, key.span().start, format!("{} '{}'", message, key.get_ref())));
+    }
+    let schema = &document.schema;
+    let tools = document.legacy_tools.iter().map(|(k, v)| (k, v)).chain(schema.tools.iter());
+    check_tables(content, tools, &schema.env, &schema.services, &mut diagnostics);
+    for profile in schema.profiles.values() {
+        check_tables(content, profile.tools.iter(), &profile.env, &profile.services, &mut diagnostics);
+    }
+
+    for (name, task) in &document.schema.tasks {
+        let task = &task.get_ref().0;
+        if task.run.trim().is_empty() {
+            diagnostics.push(Diagnostic::error(content, name.span().start, format!("task '{}' has an empty command", name.get_ref())));
+        }
+        if task.depends.iter().any(|dependency| dependency == name.get_ref()) {
+            diagnostics.push(Diagnostic::error(content, name.span().start, format!("task '{}' depends on itself", name.get_ref())));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// 检查一组 `[tools]`、`[env]`、`[services]`（顶层或某个配置方案中的）
+fn check_tables<'a>(
+    content: &str,
+    tools: impl Iterator<Item = (&'a Spanned<String>, &'a Spanned<VersionValue>)>,
+    env: &BTreeMap<Spanned<String>, Spanned<String>>,
+    services: &BTreeMap<Spanned<String>, Spanned<ServiceValue>>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (tool, value) in tools {
+        if !downloader::is_supported(tool.get_ref()) {
+            diagnostics.push(Diagnostic::error(content, tool.span().start, unknown_tool(tool.get_ref())));
+        } else if let Err(message) = check_version(tool.get_ref(), &value.get_ref().0) {
+            diagnostics.push(Diagnostic::error(content, value.span().start, message));
+        }
+    }
+    for name in services.keys() {
+        if !downloader::SERVICES.contains(&name.get_ref().as_str()) {
+            diagnostics.push(Diagnostic::error(
+                content,
+                name.span().start,
+                format!("unknown service '{}' (supported: {})", name.get_ref(), downloader::SERVICES.join(", ")),
+            ));
+        }
+    }
+    for (name, value) in env {
+        if name.get_ref().is_empty() || name.get_ref().contains(['=', '\0']) {
+            diagnostics.push(Diagnostic::error(content, name.span().start, format!("invalid environment variable name '{}'", name.get_ref())));
+        } else if RESERVED_ENV.contains(&name.get_ref().as_str()) {
+            diagnostics.push(Diagnostic::error(content, name.span().start, format!("{} is managed by enman and cannot be set in [env]", name.get_ref())));
+        }
+        if let Err(message) = parse_template(value.get_ref()) {
+            diagnostics.push(Diagnostic::error(content, value.span().start, message));
+        }
+    }
+}
+
+fn toml_message(e: &toml::de::Error) -> String {
+    e.message().trim().lines().collect::<Vec<_>>().join(": ")
+}
+
+/// `[env]` 中不能设置的变量：PATH 由 enman 计算
+pub const RESERVED_ENV: &[&str] = &["PATH"];
+
+fn check_tool(tool: &str, requested: &str) -> std::result::Result<(), String> {
+    if !downloader::is_supported(tool) {
+        return Err(unknown_tool(tool));
+    }
+    check_version(tool, requested)
+}
+
+fn unknown_tool(tool: &str) -> String {
+    format!("unknown tool '{}' (supported: {})", tool, downloader::SUPPORTED_TOOLS.join(", "))
+}
+
+/// 版本要求是否可以解析：版本号、部分版本、semver 范围、latest、lts 或 system
+fn check_version(tool: &str, requested: &str) -> std::result::Result<(), String> {
+    if requested.is_empty() {
+        return Err(format!("version of '{}' is empty", tool));
+    }
+    if version::is_system(requested) {
+        return Ok(());
+    }
+    match version::VersionSpec::parse(requested) {
+        Err(e) => Err(e.to_string()),
+        Ok(version::VersionSpec::Lts) if !version::has_lts_line(tool) => Err(format!("'{}' has no LTS release line", tool)),
+        Ok(version::VersionSpec::Exact(exact)) if !exact.starts_with(|c: char| c.is_ascii_digit()) => Err(format!(
+            "cannot parse version '{}' of '{}'; use a version number, a range such as \"^18.2\", \"latest\" or \"lts\"",
+            requested, tool
+        )),
+        Ok(_) => Ok(()),
+    }
+}
+
+/// 字节偏移量对应的 (行, 列)，都从 1 开始，列按字符计算
+fn position(content: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(content.len());
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_layouts() {
+        let config = ProjectConfig::parse_with_profile(
+            "node = \"16\"\n\n[tools]\nnode = \"18\"\njava = 17\n\n[env]\nAPP_ENV = \"dev\"\n\n[services]\nmysql = true\nredis = { port = 6380 }\nmariadb = false\n\n[settings]\nauto_install = true\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.tools.get("node").map(String::as_str), Some("18"));
+        assert_eq!(config.tool_line("node"), Some(4));
+        assert_eq!(config.tools.get("java").map(String::as_str), Some("17"));
+        assert_eq!(config.tools.len(), 2);
+        assert_eq!(config.env.get("APP_ENV").map(String::as_str), Some("dev"));
+        assert_eq!(config.services.get("mysql"), Some(&ServiceConfig::default()));
+        assert_eq!(config.services.get("redis").and_then(|s| s.port), Some(6380));
+        assert!(!config.services.contains_key("mariadb"));
+        assert_eq!(config.settings.auto_install, Some(true));
+
+        let legacy = ProjectConfig::parse_with_profile("# legacy\njava=17\nnode=18.17.0\n", None).unwrap();
+        assert_eq!(legacy.tools.get("node").map(String::as_str), Some("18.17.0"));
+        assert_eq!(legacy.tool_line("node"), Some(3));
+    }
+
+    #[test]
+    fn test_invalid_section_is_skipped() {
+        // 一节无法解析时只跳过这一节
+        let partial = ProjectConfig::parse_with_profile("[tools]\nnode = \"18\"\n\n[services]\nredis = { port = 70000 }\n\n[settings]\nauto_instal = true\n", None).unwrap();
+        assert_eq!(partial.tools.get("node").map(String::as_str), Some("18"));
+        assert!(partial.services.is_empty());
+        assert_eq!(partial.invalid_sections, vec!["services", "settings"]);
+
+        // TOML 语法错误时整个文件无法解析
+        assert!(ProjectConfig::parse_with_profile("[tools\nnode = \"18\"\n", None).is_err());
+    }
+
+    const PROFILES: &str = "[tools]\nnode = \"20\"\njava = \"17\"\n\n[services]\nmysql = true\n\n[profiles.ci]\ntools = { node = \"18\" }\nenv = { APP_ENV = \"test\" }\nservices = { mysql = false, redis = true }\n";
+
+    #[test]
+    fn test_profile_overrides_are_kept_separate() {
+        let base = ProjectConfig::parse_with_profile(PROFILES, None).unwrap();
+        assert_eq!(base.tools.get("node").map(String::as_str), Some("20"));
+        assert!(base.env.is_empty());
+        assert!(base.services.contains_key("mysql"));
+        assert_eq!(base.profiles.iter().collect::<Vec<_>>(), vec!["ci"]);
+        assert!(base.profile.is_none());
+
+        // 方案的覆盖项单独保存，合并时才应用
+        let ci = ProjectConfig::parse_with_profile(PROFILES, Some("ci")).unwrap();
+        assert_eq!(ci.tools, base.tools);
+        let overrides = ci.profile.as_deref().unwrap();
+        assert_eq!(overrides.tools.get("node").map(String::as_str), Some("18"));
+        assert_eq!(overrides.tool_line("node"), Some(9));
+        assert_eq!(overrides.env.get("APP_ENV").map(String::as_str), Some("test"));
+        assert!(overrides.disabled_services.contains("mysql"));
+    }
+
+    #[test]
+    fn test_profile_applies_when_merged() {
+        let ci = ProjectConfig::parse_with_profile(PROFILES, Some("ci")).unwrap();
+        let merged = LayeredConfig::merge(vec![(PathBuf::from(".enmanrc"), ci)]).config;
+        assert_eq!(merged.tools.get("node").map(String::as_str), Some("18"));
+        assert_eq!(merged.tools.get("java").map(String::as_str), Some("17"));
+        assert_eq!(merged.env.get("APP_ENV").map(String::as_str), Some("test"));
+        assert!(!merged.services.contains_key("mysql"));
+        assert!(merged.services.contains_key("redis"));
+    }
+
+    #[test]
+    fn test_outer_profile_overrides_inner_tools() {
+        let root = ProjectConfig::parse_with_profile(PROFILES, Some("ci")).unwrap();
+        let package = ProjectConfig::parse_with_profile("[tools]\nnode = \"16\"\n", Some("ci")).unwrap();
+        let layered = LayeredConfig::merge(vec![(PathBuf::from("root/.enmanrc"), root), (PathBuf::from("root/web/.enmanrc"), package)]);
+        assert_eq!(layered.config.tools.get("node").map(String::as_str), Some("18"));
+        assert_eq!(layered.origin("tools", "node"), Some(&Origin { file: PathBuf::from("root/.enmanrc"), line: Some(9) }));
+    }
+
+    #[test]
+    fn test_undefined_profile_uses_top_level() {
+        let legacy = ProjectConfig::parse_with_profile(PROFILES, Some("legacy")).unwrap();
+        assert!(legacy.profile.is_none());
+        let merged = LayeredConfig::merge(vec![(PathBuf::from(".enmanrc"), legacy)]).config;
+        assert_eq!(merged.tools.get("node").map(String::as_str), Some("20"));
+        assert!(merged.services.contains_key("mysql"));
+    }
+
+    #[test]
+    fn test_validate_profiles() {
+        let diagnostics = validate("[profiles.legacy.tools]\nnod = \"16\"\n\n[profiles.ci.env]\nPATH = \"/bin\"\n");
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column)).collect::<Vec<_>>(), vec![(2, 1), (5, 1)]);
+        // 配置方案只能覆盖 [tools]、[env] 和 [services]
+        assert_eq!(validate("[profiles.ci]\nsettings = { auto_install = true }\n").len(), 1);
+    }
+
+    #[test]
+    fn test_validate_reports_positions() {
+        let content = "[tools]\nnod = \"18\"\npython = \"three\"\njava = \"lts\"\n\n[services]\npostgres = true\n\n[extra]\nx = 1\n";
+        let found: Vec<(usize, usize)> = validate(content).iter().map(|d| (d.line, d.column)).collect();
+        assert_eq!(found, vec![(2, 1), (3, 10), (7, 1), (9, 2)]);
+
+        let diagnostics = validate("[settings]\nauto_instal = true\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 1));
+        assert!(diagnostics[0].message.contains("auto_instal"));
+
+        let diagnostics = validate("[env]\nJAVA_HOME = \"{{tools.java.home}}\"\nURL = \"{{services.mysql.host}}\"\nPATH = \"/bin\"\n");
+        assert_eq!(diagnostics.iter().map(|d| (d.line, d.column)).collect::<Vec<_>>(), vec![(3, 7), (4, 1)]);
+
+        assert!(validate("[tools]\nnode = \"18\"\n").is_empty());
+    }
+}
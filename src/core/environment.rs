@@ -0,0 +1,419 @@
+// src/core/environment.rs
+// 计算某个目录下生效的工具版本需要的环境变量（PATH、JAVA_HOME、VIRTUAL_ENV）
+// 和 .enmanrc 中定义的项目变量（env_files 和 `[env]`），
+// 供 shell 集成（enman activate / hook-env）、enman exec 和 shim 使用
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use crate::core::enmanrc::{self, LayeredConfig, Reference, TemplatePart, ToolField};
+use crate::core::paths::EnvManPaths;
+use crate::core::{resolve, settings};
+use crate::downloader;
+
+/// 上一次 hook 加到 PATH 前面的目录，下一次 hook 时先移除它们
+pub const PATH_ADDED_VAR: &str = "__ENMAN_PATH_ADDED";
+/// 上一次 hook 导出的变量名（逗号分隔），离开项目时取消设置或恢复原值
+pub const EXPORTED_VAR: &str = "__ENMAN_EXPORTED";
+/// hook 覆盖的变量原来的值（JSON 对象），离开项目时恢复
+pub const SAVED_VAR: &str = "__ENMAN_SAVED";
+
+/// 工具版本需要的环境
+#[derive(Debug, Default)]
+pub struct ToolEnv {
+    /// 需要加到 PATH 最前面的目录，按优先级排列
+    pub path: Vec<PathBuf>,
+    pub vars: BTreeMap<String, String>,
+}
+
+/// 需要在 shell 中执行的环境变更
+#[derive(Debug, Default, PartialEq)]
+pub struct EnvChanges {
+    pub set: Vec<(String, String)>,
+    pub unset: Vec<String>,
+}
+
+/// 计算 cwd 下生效的、已安装的工具版本需要的 PATH 目录和环境变量
+///
+/// 设置为 system 或尚未安装的版本不加入 PATH，仍由 shim 处理。
+pub fn tool_env(paths: &EnvManPaths, cwd: &Path) -> ToolEnv {
+    tool_env_with(paths, cwd, &[])
+}
+
+/// 与 `tool_env` 相同，但 overrides 中列出的工具使用指定的精确版本（enman exec）
+///
+/// 被覆盖的工具同时导出 ENMAN_<TOOL>_VERSION，子进程再经过 shim 时也解析到同一版本。
+pub fn tool_env_with(paths: &EnvManPaths, cwd: &Path, overrides: &[(String, String)]) -> ToolEnv {
+    let mut tool_env = ToolEnv::default();
+    // 加入环境的工具 → 精确版本，展开 `[env]` 中的引用时使用
+    let mut versions = BTreeMap::new();
+
+    for (tool, version) in overrides {
+        tool_env.add_tool(paths, tool, version);
+        tool_env.vars.insert(resolve::env_var_name(tool), version.clone());
+        versions.insert(tool.clone(), version.clone());
+    }
+
+    for tool in resolve::known_tools(paths) {
+        if overrides.iter().any(|(name, _)| *name == tool) {
+            continue;
+        }
+        let Some(resolution) = resolve::resolve(paths, &tool, cwd) else {
+            continue;
+        };
+        if resolution.is_system() || !resolution.installed {
+            continue;
+        }
+        tool_env.add_tool(paths, &tool, &resolution.version);
+        versions.insert(tool, resolution.version);
+    }
+
+    // Python 项目中的虚拟环境（.venv）优先于解释器本身
+    if let Some(venv) = find_venv(cwd) {
+        tool_env.path.insert(0, venv_bin(&venv));
+        tool_env.vars.insert("VIRTUAL_ENV".to_string(), venv.display().to_string());
+    }
+
+    // 项目变量最后加入，可以覆盖上面的 JAVA_HOME 等
+    let layered = resolve::layered_config(cwd);
+    tool_env.vars.extend(project_vars(paths, &layered, |tool| versions.get(tool).cloned()));
+
+    tool_env
+}
+
+/// .enmanrc 定义的项目变量：先读取 env_files 中的 .env 文件，再计算 `[env]`，后者覆盖前者
+///
+/// version_of 返回工具在当前环境中已安装的精确版本。引用了未安装的工具时给出警告并跳过该变量；
+/// 不存在的 .env 文件被忽略（它们通常不提交到仓库）。
+pub fn project_vars(
+    paths: &EnvManPaths,
+    layered: &LayeredConfig,
+    version_of: impl Fn(&str) -> Option<String>,
+) -> BTreeMap<String, String> {
+    let mut vars = BTreeMap::new();
+    for file in &layered.env_files {
+        if let Ok(content) = std::fs::read_to_string(file) {
+            vars.extend(parse_dotenv(&content));
+        }
+    }
+
+    let config = &layered.config;
+    let mut expanded = BTreeMap::new();
+    for (name, value) in &config.env {
+        if enmanrc::RESERVED_ENV.contains(&name.as_str()) {
+            settings::warn(format_args!("{} cannot be set in [env]; ignoring it", name));
+            continue;
+        }
+        let lookup = |reference: &Reference| -> Result<String, String> {
+            match reference {
+                Reference::Tool { tool, field } => {
+                    let version = version_of(tool).ok_or_else(|| format!("no installed version of {} is active", tool))?;
+                    Ok(match field {
+                        ToolField::Home => paths.install_dir(tool).join(&version).display().to_string(),
+                        ToolField::Version => version,
+                        ToolField::Bin => paths.install_bin_path(tool, &version).display().to_string(),
+                    })
+                }
+                Reference::ServicePort(service) => config
+                    .services
+                    .get(service)
+                    .and_then(|s| s.port)
+                    .or_else(|| downloader::default_port(service))
+                    .map(|port| port.to_string())
+                    .ok_or_else(|| format!("{} has no port", service)),
+                Reference::Env(var) => vars
+                    .get(var)
+                    .cloned()
+                    .or_else(|| env::var(var).ok())
+                    .ok_or_else(|| format!("{} is not set", var)),
+            }
+        };
+        match expand(value, lookup) {
+            Ok(value) => {
+                expanded.insert(name.clone(), value);
+            }
+            Err(e) => settings::warn(format_args!("skipping {} from [env]: {}", name, e)),
+        }
+    }
+    vars.extend(expanded);
+    vars
+}
+
+/// 展开值中的引用
+fn expand(value: &str, lookup: impl Fn(&Reference) -> Result<String, String>) -> Result<String, String> {
+    let mut out = String::new();
+    for part in enmanrc::parse_template(value)? {
+        match part {
+            TemplatePart::Text(text) => out.push_str(&text),
+            TemplatePart::Reference(reference) => out.push_str(&lookup(&reference)?),
+        }
+    }
+    Ok(out)
+}
+
+/// 解析 .env 文件：每行 `NAME=value`，可以带 `export ` 前缀，# 开头的行是注释；
+/// 值可以用单引号或双引号括起来，双引号中支持 \n、\" 和 \\ 转义，不带引号的值中 ` #` 之后是注释
+pub fn parse_dotenv(content: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) || enmanrc::RESERVED_ENV.contains(&name) {
+            continue;
+        }
+        let value = value.trim();
+        let value = if let Some(quoted) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+            let mut out = String::new();
+            let mut chars = quoted.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    out.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('n') => out.push('\n'),
+                    Some(other) => out.push(other),
+                    None => out.push('\\'),
+                }
+            }
+            out
+        } else if let Some(quoted) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            quoted.to_string()
+        } else {
+            value.split(" #").next().unwrap_or_default().trim_end().to_string()
+        };
+        vars.push((name.to_string(), value));
+    }
+    vars
+}
+
+impl ToolEnv {
+    /// 加入某个已安装工具版本的 bin 目录和工具特有的变量（JAVA_HOME）
+    fn add_tool(&mut self, paths: &EnvManPaths, tool: &str, version: &str) {
+        let bin = paths.install_bin_path(tool, version);
+        if !self.path.contains(&bin) {
+            self.path.push(bin);
+        }
+        if matches!(tool, "java" | "jdk") && !self.vars.contains_key("JAVA_HOME") {
+            let java_home = paths.install_dir(tool).join(version);
+            self.vars.insert("JAVA_HOME".to_string(), java_home.display().to_string());
+        }
+    }
+
+    /// 在 current 前面加上本环境的目录后得到的 PATH
+    pub fn path_var(&self, current: Option<&OsStr>) -> OsString {
+        let current: Vec<PathBuf> = current.map(|v| env::split_paths(v).collect()).unwrap_or_default();
+        env::join_paths(rewrite_path(&current, &[], &self.path)).unwrap_or_else(|_| OsString::new())
+    }
+}
+
+/// 从 cwd 向上查找项目的 .venv 虚拟环境
+fn find_venv(cwd: &Path) -> Option<PathBuf> {
+    resolve::project_dirs(cwd)
+        .into_iter()
+        .map(|dir| dir.join(".venv"))
+        .find(|venv| venv.join("pyvenv.cfg").is_file())
+}
+
+fn venv_bin(venv: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv.join("Scripts")
+    } else {
+        venv.join("bin")
+    }
+}
+
+/// 根据当前进程的环境，计算进入 cwd 时 shell 需要执行的变更
+pub fn hook_changes(paths: &EnvManPaths, cwd: &Path) -> EnvChanges {
+    let tool_env = tool_env(paths, cwd);
+    let mut changes = EnvChanges::default();
+
+    // PATH：移除上一次加入的目录，再把本次的工具目录和 shims 目录放到最前面
+    let mut added = tool_env.path.clone();
+    added.push(paths.shims.clone());
+    let previous: Vec<PathBuf> = env::var_os(PATH_ADDED_VAR)
+        .map(|v| env::split_paths(&v).collect())
+        .unwrap_or_default();
+    let current: Vec<PathBuf> = env::var_os("PATH")
+        .map(|v| env::split_paths(&v).collect())
+        .unwrap_or_default();
+    let new_path = rewrite_path(&current, &previous, &added);
+    changes.set.push(("PATH".to_string(), join(&new_path)));
+    changes.set.push((PATH_ADDED_VAR.to_string(), join(&added)));
+
+    export_changes(&tool_env.vars, |name| env::var(name).ok(), &mut changes);
+    changes
+}
+
+/// 导出 vars，并取消上一次导出、这次不再需要的变量
+///
+/// 第一次覆盖用户已经设置的变量（如自己的 JAVA_HOME）时把原值记在 SAVED_VAR 中，
+/// 不再需要这个变量时恢复原值而不是取消设置。current 读取 shell 当前的环境变量。
+fn export_changes(vars: &BTreeMap<String, String>, current: impl Fn(&str) -> Option<String>, changes: &mut EnvChanges) {
+    let previously_exported = current(EXPORTED_VAR).unwrap_or_default();
+    let previously_exported: Vec<&str> = previously_exported.split(',').filter(|n| !n.is_empty()).collect();
+    let mut saved: BTreeMap<String, String> = current(SAVED_VAR)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    for name in &previously_exported {
+        if vars.contains_key(*name) {
+            continue;
+        }
+        match saved.remove(*name) {
+            Some(value) => changes.set.push((name.to_string(), value)),
+            None => changes.unset.push(name.to_string()),
+        }
+    }
+
+    for (name, value) in vars {
+        if !previously_exported.contains(&name.as_str()) {
+            if let Some(original) = current(name) {
+                saved.insert(name.clone(), original);
+            }
+        }
+        changes.set.push((name.clone(), value.clone()));
+    }
+
+    if vars.is_empty() {
+        changes.unset.push(EXPORTED_VAR.to_string());
+    } else {
+        let names: Vec<&str> = vars.keys().map(String::as_str).collect();
+        changes.set.push((EXPORTED_VAR.to_string(), names.join(",")));
+    }
+    if saved.is_empty() {
+        changes.unset.push(SAVED_VAR.to_string());
+    } else {
+        changes.set.push((SAVED_VAR.to_string(), serde_json::to_string(&saved).unwrap_or_default()));
+    }
+}
+
+/// 从 current 中去掉 previous 和 added 中的目录，再把 added 放到最前面
+pub fn rewrite_path(current: &[PathBuf], previous: &[PathBuf], added: &[PathBuf]) -> Vec<PathBuf> {
+    let mut path = added.to_vec();
+    path.extend(
+        current
+            .iter()
+            .filter(|dir| !previous.contains(dir) && !added.contains(dir))
+            .cloned(),
+    );
+    path
+}
+
+fn join(dirs: &[PathBuf]) -> String {
+    env::join_paths(dirs)
+        .unwrap_or_else(|_| OsString::new())
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_env_and_path_rewrite() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(tmp.path().join(".enman"));
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(paths.install_bin_path("java", "17")).unwrap();
+        std::fs::create_dir_all(project.join(".venv")).unwrap();
+        std::fs::write(project.join(".venv").join("pyvenv.cfg"), "home = /usr/bin\n").unwrap();
+        std::fs::write(project.join(".enman-version"), "java@17\n").unwrap();
+
+        let tool_env = tool_env(&paths, &project);
+        assert_eq!(
+            tool_env.vars.get("JAVA_HOME").map(String::as_str),
+            Some(paths.install_dir("java").join("17").to_str().unwrap())
+        );
+        assert!(tool_env.vars.contains_key("VIRTUAL_ENV"));
+        assert_eq!(tool_env.path[0], venv_bin(&project.join(".venv")));
+        assert!(tool_env.path.contains(&paths.install_bin_path("java", "17")));
+
+        // enman exec 指定的版本优先于项目文件
+        std::fs::create_dir_all(paths.install_bin_path("java", "21")).unwrap();
+        let exec_env = tool_env_with(&paths, &project, &[("java".to_string(), "21".to_string())]);
+        assert!(!exec_env.path.contains(&paths.install_bin_path("java", "17")));
+        assert_eq!(exec_env.path[1], paths.install_bin_path("java", "21"));
+        assert_eq!(exec_env.vars.get("ENMAN_JAVA_VERSION").map(String::as_str), Some("21"));
+
+        let dirs = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(
+            rewrite_path(&dirs(&["/old/java/bin", "/shims", "/usr/bin"]), &dirs(&["/old/java/bin", "/shims"]), &dirs(&["/new/node/bin", "/shims"])),
+            dirs(&["/new/node/bin", "/shims", "/usr/bin"])
+        );
+    }
+
+    #[test]
+    fn test_export_changes_restores_user_values() {
+        let vars = |list: &[(&str, &str)]| list.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<BTreeMap<_, _>>();
+        let project = vars(&[("JAVA_HOME", "/enman/java/17"), ("APP_ENV", "dev")]);
+
+        // 进入项目：用户自己的 JAVA_HOME 被记录下来
+        let shell = vars(&[("JAVA_HOME", "/opt/my-own-jdk")]);
+        let mut entering = EnvChanges::default();
+        export_changes(&project, |name| shell.get(name).cloned(), &mut entering);
+        assert!(entering.set.contains(&("JAVA_HOME".to_string(), "/enman/java/17".to_string())));
+        assert!(entering.set.contains(&(SAVED_VAR.to_string(), r#"{"JAVA_HOME":"/opt/my-own-jdk"}"#.to_string())));
+
+        // 再次进入（如切换子目录）不会把 enman 设置的值当成原值
+        let mut shell = shell;
+        shell.extend(entering.set.iter().cloned());
+        let mut again = EnvChanges::default();
+        export_changes(&project, |name| shell.get(name).cloned(), &mut again);
+        assert!(again.set.contains(&(SAVED_VAR.to_string(), r#"{"JAVA_HOME":"/opt/my-own-jdk"}"#.to_string())));
+
+        // 离开项目：恢复原值，取消 enman 新增的变量
+        let mut leaving = EnvChanges::default();
+        export_changes(&BTreeMap::new(), |name| shell.get(name).cloned(), &mut leaving);
+        assert!(leaving.set.contains(&("JAVA_HOME".to_string(), "/opt/my-own-jdk".to_string())));
+        assert_eq!(leaving.unset, vec!["APP_ENV", EXPORTED_VAR, SAVED_VAR]);
+    }
+
+    #[test]
+    fn test_project_vars() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(tmp.path().join(".enman"));
+        let project = tmp.path().join("project");
+        std::fs::create_dir_all(paths.install_bin_path("java", "17.0.9")).unwrap();
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            project.join(".enmanrc"),
+            r#"env_files = [".env"]
+
+[tools]
+java = "17"
+
+[env]
+JAVA_HOME = "{{ tools.java.home }}/jre"
+DATABASE_URL = "mysql://{{env.DB_USER}}@localhost:{{services.mysql.port}}/app"
+NODE_OPTIONS = "--max-old-space-size={{env.HEAP}}"
+APP_ENV = "dev"
+
+[services]
+mysql = { port = 3307 }
+"#,
+        )
+        .unwrap();
+        std::fs::write(project.join(".env"), "# local\nexport DB_USER=root\nAPP_ENV=test\nGREETING=\"hello\\nworld\" \nPLAIN=a b # note\n").unwrap();
+
+        let tool_env = tool_env(&paths, &project);
+        let var = |name: &str| tool_env.vars.get(name).map(String::as_str);
+        let java_home = paths.install_dir("java").join("17.0.9").join("jre");
+        assert_eq!(var("JAVA_HOME"), java_home.to_str());
+        assert_eq!(var("DATABASE_URL"), Some("mysql://root@localhost:3307/app"));
+        assert_eq!(var("APP_ENV"), Some("dev"));
+        assert_eq!(var("GREETING"), Some("hello\nworld"));
+        assert_eq!(var("PLAIN"), Some("a b"));
+        // 引用的变量未定义时跳过该变量
+        assert_eq!(var("NODE_OPTIONS"), None);
+    }
+}
@@ -0,0 +1,102 @@
+// src/core/local_version.rs
+// 项目目录中的 .enman-version：每行一个 `tool@version`，可以同时固定多个工具
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = ".enman-version";
+
+/// .enman-version 文件，修改时保留注释、空行和其他工具的条目
+#[derive(Debug)]
+pub struct LocalVersionFile {
+    pub path: PathBuf,
+    lines: Vec<String>,
+}
+
+impl LocalVersionFile {
+    /// 读取 dir 下的 .enman-version，文件不存在时返回空文件
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(FILE_NAME);
+        let lines = if path.exists() {
+            fs::read_to_string(&path)?.lines().map(str::to_string).collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, lines })
+    }
+
+    /// 读取工具的版本，返回 (行号, 版本)
+    ///
+    /// `tool@version` 条目优先；不带工具名的裸版本号是旧的单工具格式，对任何工具生效。
+    pub fn get(&self, tool: &str) -> Option<(usize, String)> {
+        let mut bare = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            match parse_line(line) {
+                Some((Some(entry_tool), version)) if entry_tool == tool => {
+                    return Some((i + 1, version.to_string()));
+                }
+                Some((None, version)) if bare.is_none() => bare = Some((i + 1, version.to_string())),
+                _ => {}
+            }
+        }
+        bare
+    }
+
+    /// 设置工具的版本：已有条目原地替换，否则追加到末尾
+    pub fn set(&mut self, tool: &str, version: &str) {
+        let entry = format!("{}@{}", tool, version);
+        let existing = self
+            .lines
+            .iter()
+            .position(|line| matches!(parse_line(line), Some((Some(t), _)) if t == tool));
+        match existing {
+            Some(i) => self.lines[i] = entry,
+            None => self.lines.push(entry),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let mut content = self.lines.join("\n");
+        content.push('\n');
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
+
+/// 解析一行：返回 (工具名, 版本)，空行和注释返回 None
+fn parse_line(line: &str) -> Option<(Option<&str>, &str)> {
+    let line = line.split('#').next().unwrap_or("").trim();
+    if line.is_empty() {
+        return None;
+    }
+    let (tool, version) = match line.split_once('@') {
+        Some((tool, version)) => (Some(tool.trim()), version),
+        None => (None, line),
+    };
+    let version = version.trim().trim_matches('"');
+    (!version.is_empty()).then_some((tool, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_keeps_other_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(FILE_NAME), "# pinned for CI\nnode@16.20.2\n").unwrap();
+
+        let mut file = LocalVersionFile::load(dir.path()).unwrap();
+        file.set("python", "3.11.5");
+        file.set("node", "18.19.1");
+        file.save().unwrap();
+
+        let content = fs::read_to_string(dir.path().join(FILE_NAME)).unwrap();
+        assert_eq!(content, "# pinned for CI\nnode@18.19.1\npython@3.11.5\n");
+
+        let file = LocalVersionFile::load(dir.path()).unwrap();
+        assert_eq!(file.get("node"), Some((2, "18.19.1".to_string())));
+        assert_eq!(file.get("python"), Some((3, "3.11.5".to_string())));
+        assert_eq!(file.get("java"), None);
+    }
+}
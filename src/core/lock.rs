@@ -0,0 +1,168 @@
+// src/core/lock.rs
+// 项目锁文件 .enman.lock：记录每个工具实际安装的精确版本、平台、下载地址和 SHA-256，
+// 让团队成员在每台机器上得到完全相同的工具链
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::{enmanrc, local_version, resolve, settings};
+use crate::downloader::artifact::Artifact;
+
+pub const FILE_NAME: &str = ".enman.lock";
+
+const HEADER: &str = "# This file is generated by `enman lock`. Do not edit it by hand.\n\n";
+
+/// 锁文件中的一个条目；同一个工具在不同平台上各有一条
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockedTool {
+    pub name: String,
+    pub version: String,
+    pub platform: String,
+    pub url: String,
+    pub sha256: String,
+}
+
+impl LockedTool {
+    pub fn artifact(&self) -> Artifact {
+        Artifact { url: self.url.clone(), sha256: self.sha256.clone() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockFile {
+    pub version: u32,
+    #[serde(default, rename = "tool")]
+    pub tools: Vec<LockedTool>,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        Self { version: 1, tools: Vec::new() }
+    }
+}
+
+/// 当前平台标识，如 linux-x86_64、windows-x86_64、macos-aarch64
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+impl LockFile {
+    /// 读取锁文件，文件不存在时返回空锁文件
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).with_context(|| format!("Invalid lock file: {}", path.display()))
+    }
+
+    /// 当前平台上某个工具的条目
+    pub fn get(&self, tool: &str) -> Option<&LockedTool> {
+        let platform = current_platform();
+        self.tools.iter().find(|t| t.name == tool && t.platform == platform)
+    }
+
+    /// 写入条目：替换同一工具、同一平台的旧条目，其他平台的条目保持不变
+    pub fn set(&mut self, entry: LockedTool) {
+        match self
+            .tools
+            .iter_mut()
+            .find(|t| t.name == entry.name && t.platform == entry.platform)
+        {
+            Some(existing) => *existing = entry,
+            None => self.tools.push(entry),
+        }
+        self.tools
+            .sort_by(|a, b| (&a.name, &a.platform).cmp(&(&b.name, &b.platform)));
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = format!("{}{}", HEADER, toml::to_string(self)?);
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// 从 cwd 向上查找最近的 .enman.lock（与 .enmanrc 使用相同的停止规则）
+pub fn find(cwd: &Path) -> Option<PathBuf> {
+    resolve::project_dirs(cwd)
+        .into_iter()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// `enman lock` 写入的位置：已有的锁文件（`find`），否则为最近的 .enmanrc 或 .enman-version
+/// 所在的目录，都没有时为 cwd
+pub fn target(cwd: &Path) -> PathBuf {
+    if let Some(existing) = find(cwd) {
+        return existing;
+    }
+    let project_file = |dir: &PathBuf| dir.join(enmanrc::FILE_NAME).is_file() || dir.join(local_version::FILE_NAME).is_file();
+    resolve::project_dirs(cwd)
+        .into_iter()
+        .find(project_file)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(FILE_NAME)
+}
+
+/// 当前目录生效的锁文件中某个工具的条目；锁文件无法解析时给出警告并忽略
+pub fn locked(tool: &str) -> Option<LockedTool> {
+    let cwd = std::env::current_dir().ok()?;
+    let path = find(&cwd)?;
+    match LockFile::load(&path) {
+        Ok(lock) => lock.get(tool).cloned(),
+        Err(e) => {
+            settings::warn(format_args!("ignoring {}: {:#}", path.display(), e));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, version: &str, platform: &str) -> LockedTool {
+        LockedTool {
+            name: name.to_string(),
+            version: version.to_string(),
+            platform: platform.to_string(),
+            url: format!("https://example.com/{}-{}.tar.gz", name, version),
+            sha256: "ab".repeat(32),
+        }
+    }
+
+    #[test]
+    fn test_lock_roundtrip_keeps_other_platforms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+
+        let mut lock = LockFile::default();
+        lock.set(entry("node", "18.17.0", "freebsd-riscv64"));
+        lock.set(entry("node", "18.17.0", &current_platform()));
+        lock.set(entry("node", "18.19.1", &current_platform()));
+        lock.save(&path).unwrap();
+
+        let lock = LockFile::load(&path).unwrap();
+        assert_eq!(lock.tools.len(), 2);
+        assert_eq!(lock.get("node").unwrap().version, "18.19.1");
+        assert!(lock.get("java").is_none());
+        assert!(fs::read_to_string(&path).unwrap().starts_with("# This file is generated"));
+    }
+
+    #[test]
+    fn test_target_is_next_to_project_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        let sub = project.join("src").join("app");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(project.join(enmanrc::FILE_NAME), "root = true\n\n[tools]\nnode = \"18\"\n").unwrap();
+        assert_eq!(target(&sub), project.join(FILE_NAME));
+
+        // 已有的锁文件优先，不会在子目录中再创建一个
+        fs::write(project.join("src").join(local_version::FILE_NAME), "node@20\n").unwrap();
+        fs::write(project.join(FILE_NAME), HEADER).unwrap();
+        assert_eq!(target(&sub), project.join(FILE_NAME));
+    }
+}
@@ -1,3 +1,14 @@
 // src/core/mod.rs
+pub mod ecosystem;
+pub mod enmanrc;
+pub mod environment;
+pub mod local_version;
+pub mod lock;
 pub mod paths;
+pub mod resolve;
+pub mod settings;
+pub mod shim;
+pub mod shim_manager;
+#[cfg(test)]
+pub mod testing;
 pub mod version;  // 添加版本模块
@@ -1,33 +1,89 @@
 // src/core/paths.rs
+// enman 的目录布局。默认所有数据都在 ~/.enman 下；ENMAN_HOME 可以把它整体移到其他位置，
+// ENMAN_XDG=1 时按 XDG 规范拆分为数据、缓存和配置三个目录
 use anyhow::Result;
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+pub const HOME_VAR: &str = "ENMAN_HOME";
+pub const XDG_VAR: &str = "ENMAN_XDG";
+
+/// 用户主目录，只用于默认布局和向上查找项目文件时的停止位置
+pub fn user_home() -> Option<PathBuf> {
+    dirs::home_dir()
+}
 
 #[derive(Debug)]
 pub struct EnvManPaths {
-    #[allow(dead_code)] 
+    /// 数据目录：installs、shims 和 global 都在这里
+    #[allow(dead_code)]
     pub root: PathBuf,
     pub shims: PathBuf,
     pub installs: PathBuf,
     pub global: PathBuf,
+    pub cache: PathBuf,
+    /// 配置目录：全局设置 enman.json 所在的目录
+    pub config: PathBuf,
 }
 
 impl EnvManPaths {
+    /// 当前环境的目录布局，并应用全局设置中的 cache_dir
     pub fn new() -> Result<Self> {
-        let home = dirs::home_dir()
-            .ok_or_else(|| anyhow::anyhow!("Failed to get home directory"))?;
-        let root = home.join(".enman");
-        Ok(Self {
+        let mut paths = Self::detect()?;
+        // 全局设置中的 cache_dir 覆盖默认的缓存目录
+        if let Some(cache) = &crate::core::settings::global().cache_dir {
+            paths.cache = cache.clone();
+        }
+        Ok(paths)
+    }
+
+    /// 只根据环境变量确定的目录布局（不读取全局设置，读取设置文件本身时使用）
+    pub fn detect() -> Result<Self> {
+        Self::from_env(|name| std::env::var_os(name), user_home().as_deref())
+    }
+
+    /// 按优先级确定目录布局：ENMAN_HOME > ENMAN_XDG > ~/.enman
+    fn from_env(var: impl Fn(&str) -> Option<OsString>, home: Option<&Path>) -> Result<Self> {
+        let var = |name: &str| var(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+        if let Some(root) = var(HOME_VAR) {
+            return Ok(Self::from_root(root));
+        }
+
+        let home = home.ok_or_else(|| anyhow::anyhow!("Failed to get home directory"))?;
+        let xdg = var(XDG_VAR).is_some_and(|v| matches!(v.to_str(), Some("1" | "true" | "yes" | "on")));
+        if !xdg {
+            return Ok(Self::from_root(home.join(".enman")));
+        }
+
+        let xdg_dir = |name: &str, default: &str| var(name).unwrap_or_else(|| home.join(default)).join("enman");
+        let mut paths = Self::from_root(xdg_dir("XDG_DATA_HOME", ".local/share"));
+        paths.cache = xdg_dir("XDG_CACHE_HOME", ".cache");
+        paths.config = xdg_dir("XDG_CONFIG_HOME", ".config");
+        Ok(paths)
+    }
+
+    /// 以指定目录作为 enman 根目录（缓存和配置也在其中）
+    pub fn from_root(root: PathBuf) -> Self {
+        Self {
             shims: root.join("shims"),
             installs: root.join("installs"),
             global: root.join("global"),
+            cache: root.join("cache"),
+            config: root.clone(),
             root,
-        })
+        }
+    }
+
+    /// 全局设置文件
+    pub fn settings_file(&self) -> PathBuf {
+        self.config.join(crate::core::settings::FILE_NAME)
     }
 
     pub fn ensure_dirs(&self) -> Result<()> {
         std::fs::create_dir_all(&self.shims)?;
         std::fs::create_dir_all(&self.installs)?;
         std::fs::create_dir_all(&self.global)?;
+        std::fs::create_dir_all(&self.config)?;
         Ok(())
     }
 
@@ -56,7 +112,51 @@ impl EnvManPaths {
         }
     }
 
+    /// 远程版本列表的本地缓存，供命令行补全使用（补全时不能访问网络）
+    pub fn remote_versions_cache(&self, tool: &str) -> PathBuf {
+        self.cache.join("versions").join(format!("{}.txt", tool))
+    }
+
     pub fn install_dir(&self, tool: &str) -> PathBuf {
         self.installs.join(tool)
     }
-}
\ No newline at end of file
+
+    /// 列出某个工具已安装的所有版本（目录名），跳过 .locks 等隐藏目录
+    pub fn installed_versions(&self, tool: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.install_dir(tool)) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.starts_with('.'))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        let home = Path::new("/home/dev");
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| OsString::from(v))
+        };
+
+        let default = EnvManPaths::from_env(env(&[]), Some(home)).unwrap();
+        assert_eq!(default.installs, home.join(".enman/installs"));
+        assert_eq!(default.settings_file(), home.join(".enman/enman.json"));
+
+        let relocated = EnvManPaths::from_env(env(&[("ENMAN_HOME", "/srv/enman"), ("ENMAN_XDG", "1")]), None).unwrap();
+        assert_eq!(relocated.shims, Path::new("/srv/enman/shims"));
+        assert_eq!(relocated.cache, Path::new("/srv/enman/cache"));
+
+        let xdg = EnvManPaths::from_env(env(&[("ENMAN_XDG", "1"), ("XDG_CACHE_HOME", "/tmp/cache")]), Some(home)).unwrap();
+        assert_eq!(xdg.installs, home.join(".local/share/enman/installs"));
+        assert_eq!(xdg.cache, Path::new("/tmp/cache/enman"));
+        assert_eq!(xdg.settings_file(), home.join(".config/enman/enman.json"));
+    }
+}
@@ -0,0 +1,590 @@
+// src/core/resolve.rs
+// 统一的版本解析：shim、list、use 等命令都通过这里决定某个工具当前生效的版本
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::core::ecosystem;
+use crate::core::enmanrc::{self, LayeredConfig, ProjectConfig, Settings};
+use crate::core::local_version::LocalVersionFile;
+use crate::core::paths::{self, EnvManPaths};
+use crate::core::settings;
+use crate::core::version;
+
+/// 版本来源的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceKind {
+    /// 环境变量 ENMAN_<TOOL>_VERSION
+    EnvVar,
+    /// 当前目录或上级目录的 .enman-version
+    LocalVersionFile,
+    /// 当前目录或上级目录的 .enmanrc
+    Enmanrc,
+    /// 其他版本管理器的版本文件（.nvmrc、.tool-versions 等）
+    Ecosystem,
+    /// ~/.enman/global/<tool>
+    Global,
+}
+
+impl SourceKind {
+    /// 是否为项目文件中的来源；全局版本和环境变量（包括 `enman shell` 设置的）不是
+    pub fn is_local(&self) -> bool {
+        !matches!(self, SourceKind::Global | SourceKind::EnvVar)
+    }
+}
+
+/// 决定版本的具体文件和行号
+#[derive(Debug, Clone)]
+pub struct Source {
+    pub kind: SourceKind,
+    /// 决定版本的文件；来源为环境变量时是变量名
+    pub file: PathBuf,
+    /// 版本所在的行号（从 1 开始），无法确定时为 None
+    pub line: Option<usize>,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.kind == SourceKind::EnvVar {
+            return write!(f, "environment variable {}", self.file.display());
+        }
+        match self.line {
+            Some(line) => write!(f, "{}:{}", self.file.display(), line),
+            None => write!(f, "{}", self.file.display()),
+        }
+    }
+}
+
+/// 某个来源给出的版本（尚未与已安装版本比对）
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// 文件中写下的版本，可能是部分版本或别名
+    pub requested: String,
+    pub source: Source,
+}
+
+/// 解析结果
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub tool: String,
+    /// 文件中写下的版本
+    pub requested: String,
+    /// 解析后的精确版本
+    pub version: String,
+    /// 工具二进制所在目录，见 `EnvManPaths::install_bin_path`
+    pub install_path: PathBuf,
+    pub installed: bool,
+    pub source: Source,
+}
+
+impl Resolution {
+    /// 版本设置为 system，使用系统中的可执行文件
+    pub fn is_system(&self) -> bool {
+        version::is_system(&self.version)
+    }
+
+    /// 工具中某个可执行文件的完整路径
+    ///
+    /// Windows 上依次尝试 .exe、.cmd、.bat（npm、npx 是 .cmd 脚本），都不存在时返回 .exe 路径。
+    pub fn binary(&self, exe: &str) -> PathBuf {
+        if !cfg!(windows) {
+            return self.install_path.join(exe);
+        }
+        ["exe", "cmd", "bat"]
+            .iter()
+            .map(|ext| self.install_path.join(format!("{}.{}", exe, ext)))
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| self.install_path.join(format!("{}.exe", exe)))
+    }
+}
+
+/// 按优先级从高到低列出某个工具的所有候选版本，第一个即为生效的版本
+///
+/// 环境变量 ENMAN_<TOOL>_VERSION 优先于所有文件；其次是 ENMAN_PROFILE 选中的配置方案，
+/// 任何一层 .enmanrc 的方案都优先于普通的项目配置。之后从当前目录向上逐级查找
+/// （见 `project_dirs`），离当前目录越近的目录优先，同一目录内依次检查
+/// .enman-version、.enmanrc 和其他版本管理器的版本文件。都没有设置时使用全局版本。
+pub fn candidates(paths: &EnvManPaths, tool: &str, cwd: &Path) -> Vec<Candidate> {
+    candidates_with(paths, tool, cwd, |name| std::env::var(name).ok())
+}
+
+/// 与 `candidates` 相同，但通过 env 读取 ENMAN_<TOOL>_VERSION，不依赖当前进程的环境变量
+fn candidates_with(
+    paths: &EnvManPaths,
+    tool: &str,
+    cwd: &Path,
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<Candidate> {
+    let mut found = Vec::new();
+
+    let var = env_var_name(tool);
+    if let Some(requested) = env(&var) {
+        let requested = requested.trim();
+        if !requested.is_empty() {
+            found.push(Candidate {
+                requested: requested.to_string(),
+                source: Source { kind: SourceKind::EnvVar, file: PathBuf::from(var), line: None },
+            });
+        }
+    }
+
+    let dirs = project_dirs(cwd);
+    if let Some(profile) = enmanrc::profile_name(env(enmanrc::PROFILE_VAR)) {
+        for dir in &dirs {
+            let enmanrc = dir.join(enmanrc::FILE_NAME);
+            if let Some((line, requested)) = enmanrc_profile_version(&enmanrc, tool, &profile) {
+                found.push(Candidate {
+                    requested,
+                    source: Source { kind: SourceKind::Enmanrc, file: enmanrc, line },
+                });
+            }
+        }
+    }
+
+    for dir in dirs {
+        if let Ok(local_file) = LocalVersionFile::load(&dir) {
+            if let Some((line, requested)) = local_file.get(tool) {
+                found.push(Candidate {
+                    requested,
+                    source: Source { kind: SourceKind::LocalVersionFile, file: local_file.path, line: Some(line) },
+                });
+            }
+        }
+        let enmanrc = dir.join(enmanrc::FILE_NAME);
+        if let Some((line, requested)) = enmanrc_version(&enmanrc, tool) {
+            found.push(Candidate {
+                requested,
+                source: Source { kind: SourceKind::Enmanrc, file: enmanrc, line },
+            });
+        }
+        if let Some(eco) = ecosystem::find_in_dir(&dir, tool) {
+            found.push(Candidate {
+                requested: eco.version,
+                source: Source { kind: SourceKind::Ecosystem, file: eco.file, line: Some(eco.line) },
+            });
+        }
+    }
+
+    if let Some(candidate) = global_candidate(paths, tool) {
+        found.push(candidate);
+    }
+
+    found
+}
+
+/// 从 start 开始向上列出需要查找项目配置的目录（包含 start 本身）
+///
+/// 在以下位置停止：
+/// - 主目录：检查主目录本身，但不再继续向上；不在主目录下的路径一直查找到根目录
+/// - 文件系统边界：不进入与 start 不在同一文件系统上的上级目录（如挂载点之外）
+pub fn search_dirs(start: &Path, home: Option<&Path>) -> Vec<PathBuf> {
+    let start_device = device_id(start);
+    let mut dirs = Vec::new();
+
+    for dir in start.ancestors() {
+        if device_id(dir) != start_device {
+            break;
+        }
+        dirs.push(dir.to_path_buf());
+        if home == Some(dir) {
+            break;
+        }
+    }
+    dirs
+}
+
+/// 需要查找项目配置的目录（从 cwd 向上，见 `search_dirs`），
+/// 到 .enmanrc 设置了 `root = true` 的目录为止
+pub fn project_dirs(cwd: &Path) -> Vec<PathBuf> {
+    let mut dirs = search_dirs(cwd, paths::user_home().as_deref());
+    if let Some(root) = dirs.iter().position(|dir| load_enmanrc(dir).is_some_and(|config| config.root)) {
+        dirs.truncate(root + 1);
+    }
+    dirs
+}
+
+/// cwd 生效的项目配置：`project_dirs` 中所有 .enmanrc 从最外层到 cwd 逐层合并
+///
+/// 有语法错误的文件被忽略并给出警告（`enman config validate` 会报告原因）。
+pub fn layered_config(cwd: &Path) -> LayeredConfig {
+    let layers = project_dirs(cwd)
+        .into_iter()
+        .rev()
+        .filter_map(|dir| Some((dir.join(enmanrc::FILE_NAME), load_enmanrc(&dir)?)))
+        .collect();
+    LayeredConfig::merge(layers)
+}
+
+fn load_enmanrc(dir: &Path) -> Option<ProjectConfig> {
+    let path = dir.join(enmanrc::FILE_NAME);
+    if !path.is_file() {
+        return None;
+    }
+    read_enmanrc(&path, enmanrc::active_profile().as_deref())
+}
+
+/// 读取 .enmanrc；文件无法解析或有节被跳过时给出警告，版本不会被悄悄忽略
+fn read_enmanrc(path: &Path, profile: Option<&str>) -> Option<ProjectConfig> {
+    match ProjectConfig::load_with_profile(path, profile) {
+        Ok(config) => {
+            if !config.invalid_sections.is_empty() {
+                let sections: Vec<String> = config.invalid_sections.iter().map(|name| format!("[{}]", name)).collect();
+                warn_once(path, format!(
+                    "ignoring invalid {} in {}. Run `enman config validate` for details",
+                    sections.join(", "),
+                    path.display()
+                ));
+            }
+            Some(config)
+        }
+        Err(e) => {
+            warn_once(path, e);
+            None
+        }
+    }
+}
+
+/// 同一个文件的警告只输出一次（一次解析中同一个 .enmanrc 会被读取多次）
+fn warn_once(path: &Path, message: impl fmt::Display) {
+    static WARNED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+    let first = WARNED.lock().map(|mut warned| warned.insert(path.to_path_buf())).unwrap_or(true);
+    if first {
+        settings::warn(message);
+    }
+}
+
+/// 目录所在文件系统的设备号，用于检测挂载点边界
+#[cfg(unix)]
+fn device_id(dir: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(dir).ok().map(|m| m.dev())
+}
+
+/// Windows 上向上查找不会离开当前盘符，无需额外检测
+#[cfg(not(unix))]
+fn device_id(_dir: &Path) -> Option<u64> {
+    None
+}
+
+/// 覆盖某个工具版本的环境变量名，如 node → ENMAN_NODE_VERSION
+pub fn env_var_name(tool: &str) -> String {
+    format!("ENMAN_{}_VERSION", tool.to_uppercase().replace('-', "_"))
+}
+
+/// 所有可能有生效版本的工具：支持的工具、已安装的工具以及设置了全局版本的工具
+pub fn known_tools(paths: &EnvManPaths) -> Vec<String> {
+    let mut tools: Vec<String> = crate::downloader::SUPPORTED_TOOLS.iter().map(|t| t.to_string()).collect();
+    for dir in [&paths.installs, &paths.global] {
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            tools.extend(entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()));
+        }
+    }
+    tools.sort();
+    tools.dedup();
+    tools
+}
+
+/// 全局版本（~/.enman/global/<tool>）
+pub fn global_candidate(paths: &EnvManPaths, tool: &str) -> Option<Candidate> {
+    let file = paths.global_version_file(tool);
+    let content = std::fs::read_to_string(&file).ok()?;
+    let requested = parse_version_entry(&content, tool)?;
+    Some(Candidate {
+        requested,
+        source: Source { kind: SourceKind::Global, file, line: None },
+    })
+}
+
+/// 解析某个工具在 cwd 下生效的版本，未配置任何版本时返回 None
+pub fn resolve(paths: &EnvManPaths, tool: &str, cwd: &Path) -> Option<Resolution> {
+    candidates(paths, tool, cwd)
+        .into_iter()
+        .next()
+        .map(|candidate| resolve_candidate(paths, tool, candidate))
+}
+
+/// 以当前工作目录解析
+pub fn resolve_current(paths: &EnvManPaths, tool: &str) -> anyhow::Result<Option<Resolution>> {
+    let cwd = std::env::current_dir()?;
+    Ok(resolve(paths, tool, &cwd))
+}
+
+/// 将候选版本与已安装版本比对，得到精确版本和安装路径
+pub fn resolve_candidate(paths: &EnvManPaths, tool: &str, candidate: Candidate) -> Resolution {
+    // system 不对应任何安装目录，可执行文件由 shim 在 PATH 上查找
+    if version::is_system(&candidate.requested) {
+        return Resolution {
+            tool: tool.to_string(),
+            requested: candidate.requested,
+            version: version::SYSTEM.to_string(),
+            install_path: PathBuf::new(),
+            installed: true,
+            source: candidate.source,
+        };
+    }
+    let version = version::resolve_installed(paths, tool, &candidate.requested)
+        .unwrap_or_else(|| candidate.requested.clone());
+    let installed = paths.install_dir(tool).join(&version).is_dir();
+    Resolution {
+        tool: tool.to_string(),
+        requested: candidate.requested,
+        install_path: paths.install_bin_path(tool, &version),
+        version,
+        installed,
+        source: candidate.source,
+    }
+}
+
+/// 解析全局版本文件内容：`tool@version` 只对同名工具生效，
+/// 不带工具名的裸版本号（旧格式）对任何工具生效
+pub fn parse_version_entry(content: &str, tool: &str) -> Option<String> {
+    let content = content.trim();
+    let version = match content.split_once('@') {
+        Some((file_tool, version)) if file_tool.trim() == tool => version,
+        Some(_) => return None,
+        None => content,
+    };
+    let version = version.trim().trim_matches('"');
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// 从 .enmanrc 中读取工具版本（不含配置方案），返回 (行号, 版本)
+///
+/// 文件格式见 `enmanrc::ProjectConfig`；有语法错误的文件被忽略并给出警告（`enman config validate` 会报告原因）。
+pub fn enmanrc_version(path: &Path, tool: &str) -> Option<(Option<usize>, String)> {
+    if !path.is_file() {
+        return None;
+    }
+    let config = read_enmanrc(path, None)?;
+    let version = config.tools.get(tool)?.clone();
+    Some((config.tool_line(tool), version))
+}
+
+/// .enmanrc 中选中的配置方案为工具指定的版本，返回 (行号, 版本)
+fn enmanrc_profile_version(path: &Path, tool: &str, profile: &str) -> Option<(Option<usize>, String)> {
+    if !path.is_file() {
+        return None;
+    }
+    let profile = read_enmanrc(path, Some(profile))?.profile?;
+    let version = profile.tools.get(tool)?.clone();
+    Some((profile.tool_line(tool), version))
+}
+
+/// 逐层合并后 .enmanrc 中 `[settings]` 的某项设置，离 cwd 最近的设置生效
+pub fn enmanrc_setting<T>(cwd: &Path, setting: impl Fn(&Settings) -> Option<T>) -> Option<T> {
+    setting(&layered_config(cwd).config.settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::testing::{self, no_env, Fixture};
+
+    #[test]
+    fn test_enmanrc_layouts() {
+        let fixture = Fixture::new();
+        let rc = fixture.write(".enmanrc", "# project\n[tools]\nnode = \"18\"\npython = \"3.11.5\"\n");
+        assert_eq!(enmanrc_version(&rc, "python"), Some((Some(4), "3.11.5".to_string())));
+
+        std::fs::write(&rc, "node = \"20.11.0\"\n").unwrap();
+        assert_eq!(enmanrc_version(&rc, "node"), Some((Some(1), "20.11.0".to_string())));
+
+        std::fs::write(&rc, "# legacy\njava=17\nnode=18.17.0\n").unwrap();
+        assert_eq!(enmanrc_version(&rc, "java"), Some((Some(2), "17".to_string())));
+        assert_eq!(enmanrc_version(&rc, "node"), Some((Some(3), "18.17.0".to_string())));
+        assert_eq!(enmanrc_version(&rc, "redis"), None);
+    }
+
+    #[test]
+    fn test_enmanrc_settings() {
+        let fixture = Fixture::new();
+        let project_rc = fixture.write("project/.enmanrc", "[tools]\nnode = \"18\"\n\n[settings]\nauto_install = true\n");
+        let app = fixture.dir("project/app");
+        fixture.write("project/app/.enmanrc", "[tools]\nnode = \"20\"\n");
+        assert_eq!(enmanrc_setting(&app, |s| s.auto_install), Some(true));
+        assert_eq!(enmanrc_version(&project_rc, "node"), Some((Some(2), "18".to_string())));
+
+        fixture.write("project/app/.enmanrc", "[settings]\nauto_install = false\n");
+        assert_eq!(enmanrc_setting(&app, |s| s.auto_install), Some(false));
+    }
+
+    #[test]
+    fn test_invalid_section_keeps_tool_versions() {
+        let fixture = Fixture::new();
+        let project = fixture.dir("project");
+        fixture.write("project/.enmanrc", "[tools]\nnode = \"18\"\n\n[settings]\nauto_instal = true\n");
+
+        // [settings] 中的拼写错误只跳过这一节
+        assert_eq!(candidates_with(&fixture.paths, "node", &project, no_env)[0].requested, "18");
+        assert_eq!(enmanrc_setting(&project, |s| s.auto_install), None);
+    }
+
+    #[test]
+    fn test_unparsable_enmanrc_is_ignored() {
+        let fixture = Fixture::new();
+        let project = fixture.dir("project");
+        fixture.write(".enman-version", "node@16\n");
+        fixture.write("project/.enmanrc", "[tools\nnode = \"18\"\n");
+
+        // 有语法错误的文件被跳过，上级目录的版本仍然生效
+        assert_eq!(candidates_with(&fixture.paths, "node", &project, no_env)[0].requested, "16");
+    }
+
+    /// monorepo：仓库顶层设置了 root = true，上面还有一个不属于仓库的 .enmanrc
+    fn monorepo() -> (Fixture, PathBuf) {
+        let fixture = Fixture::new();
+        fixture.write(".enmanrc", "[tools]\npython = \"3.11\"\n");
+        fixture.write(
+            "repo/.enmanrc",
+            "root = true\n\n[tools]\njava = \"17\"\nnode = \"18\"\n\n[env]\nAPP_ENV = \"dev\"\n\n[services]\nmysql = true\n",
+        );
+        fixture.write("repo/packages/web/.enmanrc", "[tools]\nnode = \"20\"\n\n[services]\nmysql = false\nredis = true\n");
+        let web = fixture.dir("repo/packages/web");
+        (fixture, web)
+    }
+
+    #[test]
+    fn test_layered_config_inner_overrides_outer() {
+        let (fixture, web) = monorepo();
+        let repo_rc = fixture.root().join("repo/.enmanrc");
+        let web_rc = web.join(".enmanrc");
+
+        let layered = layered_config(&web);
+        assert_eq!(layered.files, vec![repo_rc.clone(), web_rc.clone()]);
+        assert_eq!(layered.config.tools.get("java").map(String::as_str), Some("17"));
+        assert_eq!(layered.config.tools.get("node").map(String::as_str), Some("20"));
+        assert_eq!(layered.config.env.get("APP_ENV").map(String::as_str), Some("dev"));
+        // 内层文件关闭了外层声明的 mysql
+        assert_eq!(layered.config.services.keys().collect::<Vec<_>>(), vec!["redis"]);
+        let origin = layered.origin("tools", "node").unwrap();
+        assert_eq!((origin.file.as_path(), origin.line), (web_rc.as_path(), Some(2)));
+        assert_eq!(layered.origin("env", "APP_ENV").unwrap().file, repo_rc);
+    }
+
+    #[test]
+    fn test_layered_config_stops_at_root() {
+        let (fixture, web) = monorepo();
+
+        // root = true 之上的目录不再参与合并和版本解析
+        assert!(!layered_config(&web).config.tools.contains_key("python"));
+        assert!(candidates_with(&fixture.paths, "python", &web, no_env).is_empty());
+    }
+
+    #[test]
+    fn test_parent_version_file_applies_in_subdirectories() {
+        let fixture = Fixture::new();
+        fixture.write("project/.enman-version", "node@18.19.1\npython@3.11.5\n");
+        let src = fixture.dir("project/packages/web/src");
+
+        let found = candidates_with(&fixture.paths, "node", &src, no_env);
+        assert_eq!(found[0].requested, "18.19.1");
+        assert_eq!(found[0].source.kind, SourceKind::LocalVersionFile);
+    }
+
+    #[test]
+    fn test_nearest_directory_wins() {
+        let fixture = Fixture::new();
+        fixture.write("project/.enman-version", "node@18.19.1\npython@3.11.5\n");
+        fixture.write("project/packages/web/.enmanrc", "[tools]\nnode = \"20\"\n");
+        let src = fixture.dir("project/packages/web/src");
+
+        // 更近目录中的 .enmanrc 覆盖上级目录的 .enman-version
+        let found = candidates_with(&fixture.paths, "node", &src, no_env);
+        assert_eq!(found[0].requested, "20");
+        assert_eq!(found[1].requested, "18.19.1");
+    }
+
+    #[test]
+    fn test_version_file_wins_within_directory() {
+        let fixture = Fixture::new();
+        fixture.write("project/.enman-version", "python@3.11.5\n");
+        fixture.write("project/packages/web/.enmanrc", "[tools]\nnode = \"20\"\n");
+        let version_file = fixture.write("project/packages/web/.enman-version", "node@16.20.2\n");
+        let src = fixture.dir("project/packages/web/src");
+
+        // 同一目录内 .enman-version 优先于 .enmanrc
+        let found = candidates_with(&fixture.paths, "node", &src, no_env);
+        assert_eq!(found[0].requested, "16.20.2");
+        assert_eq!(found[0].source.file, version_file);
+        assert_eq!(found[1].requested, "20");
+        // 没有在更近目录中设置的工具仍使用上级目录的版本
+        assert_eq!(candidates_with(&fixture.paths, "python", &src, no_env)[0].requested, "3.11.5");
+    }
+
+    #[test]
+    fn test_profile_overrides_every_layer() {
+        let fixture = Fixture::new();
+        let root_rc = fixture.write("project/.enmanrc", "[profiles.ci]\ntools = { node = \"18\" }\n");
+        fixture.write("project/packages/web/.enmanrc", "[tools]\nnode = \"20\"\n");
+        fixture.write("project/packages/web/.enman-version", "node@16.20.2\n");
+        let src = fixture.dir("project/packages/web/src");
+
+        // 选中的配置方案优先于任何一层的普通配置，包括更近目录中的 .enman-version
+        let found = candidates_with(&fixture.paths, "node", &src, testing::env(&[(enmanrc::PROFILE_VAR, "ci")]));
+        assert_eq!(found[0].requested, "18");
+        assert_eq!((found[0].source.file.clone(), found[0].source.line), (root_rc, Some(2)));
+        assert_eq!(found[1].requested, "16.20.2");
+        assert_eq!(candidates_with(&fixture.paths, "node", &src, no_env)[0].requested, "16.20.2");
+    }
+
+    #[test]
+    fn test_undefined_profile_uses_plain_config() {
+        let fixture = Fixture::new();
+        fixture.write("project/.enmanrc", "[tools]\nnode = \"20\"\n\n[profiles.ci]\ntools = { node = \"18\" }\n");
+        let project = fixture.dir("project");
+
+        let found = candidates_with(&fixture.paths, "node", &project, testing::env(&[(enmanrc::PROFILE_VAR, "legacy")]));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].requested, "20");
+    }
+
+    #[test]
+    fn test_search_stops_at_home() {
+        let tmp = tempfile::tempdir().unwrap();
+        let home = tmp.path().join("home");
+        let project = home.join("work").join("app");
+        std::fs::create_dir_all(&project).unwrap();
+
+        let dirs = search_dirs(&project, Some(&home));
+        assert_eq!(dirs, vec![project.clone(), home.join("work"), home.clone()]);
+
+        // 不在主目录下的路径一直查找到文件系统边界
+        let outside = tmp.path().join("srv");
+        std::fs::create_dir_all(&outside).unwrap();
+        let dirs = search_dirs(&outside, Some(&home));
+        assert_eq!(dirs[0], outside);
+        assert!(dirs.contains(&tmp.path().to_path_buf()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_search_stops_at_filesystem_boundary() {
+        // /proc 是独立挂载的 procfs，向上查找不应越过它进入 /
+        let dirs = search_dirs(Path::new("/proc/sys"), None);
+        assert_eq!(dirs, vec![PathBuf::from("/proc/sys"), PathBuf::from("/proc")]);
+    }
+
+    #[test]
+    fn test_env_var_overrides_files() {
+        let fixture = Fixture::new();
+        fixture.write(".enman-version", "redis@7.2.4\n");
+
+        let found = candidates_with(&fixture.paths, "redis", fixture.root(), testing::env(&[("ENMAN_REDIS_VERSION", "7.0.5")]));
+
+        assert_eq!(found[0].requested, "7.0.5");
+        assert_eq!(found[0].source.to_string(), "environment variable ENMAN_REDIS_VERSION");
+        // 环境变量覆盖不是项目文件，list 不能把它显示为 local
+        assert!(!found[0].source.kind.is_local());
+        assert!(found[1].source.kind.is_local());
+        assert_eq!(found[1].requested, "7.2.4");
+    }
+
+    #[test]
+    fn test_parse_version_entry() {
+        assert_eq!(parse_version_entry("node@18.17.0\n", "node").as_deref(), Some("18.17.0"));
+        assert_eq!(parse_version_entry("python@3.11", "node"), None);
+        assert_eq!(parse_version_entry("\"20\"", "node").as_deref(), Some("20"));
+        assert_eq!(parse_version_entry("  ", "node"), None);
+    }
+}
@@ -0,0 +1,290 @@
+// src/core/settings.rs
+// 全局设置 ~/.enman/enman.json：`enman config set/get/unset/list --global` 读写，
+// 语言、下载镜像、自动安装、缓存目录、代理和日志级别都从这里读取
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::core::paths::EnvManPaths;
+use crate::downloader;
+
+pub const FILE_NAME: &str = "enman.json";
+
+/// 所有可以设置的键；`mirrors.<tool>` 对每个支持的工具各有一个
+pub const KEYS: &[&str] = &["language", "mirrors.<tool>", "auto_install", "cache_dir", "proxy", "log_level"];
+
+const LANGUAGES: &[&str] = &["en", "zh"];
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalSettings {
+    /// 界面语言（en / zh），未设置时根据系统语言检测
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 工具 → 下载镜像地址，优先于内置的下载源
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub mirrors: BTreeMap<String, String>,
+    /// shim 遇到未安装的版本时自动安装（项目 .enmanrc 和 ENMAN_AUTO_INSTALL 优先）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_install: Option<bool>,
+    /// 下载缓存目录，默认为 enman 目录布局中的缓存目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_dir: Option<PathBuf>,
+    /// 下载使用的 HTTP/HTTPS/SOCKS5 代理
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// 输出哪些级别的诊断信息，默认 warn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<LogLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Some(Self::Error),
+            "warn" | "warning" => Some(Self::Warn),
+            "info" => Some(Self::Info),
+            "debug" => Some(Self::Debug),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+        }
+    }
+}
+
+impl GlobalSettings {
+    /// 读取设置文件，文件不存在时返回默认设置
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&content).with_context(|| format!("Invalid settings file: {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// 读取某个键的值，未设置时返回 None
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match parse_key(key)? {
+            Key::Language => self.language.clone(),
+            Key::Mirror(tool) => self.mirrors.get(&tool).cloned(),
+            Key::AutoInstall => self.auto_install.map(|v| v.to_string()),
+            Key::CacheDir => self.cache_dir.as_ref().map(|p| p.display().to_string()),
+            Key::Proxy => self.proxy.clone(),
+            Key::LogLevel => self.log_level.map(|l| l.as_str().to_string()),
+        })
+    }
+
+    /// 校验并设置某个键
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        match parse_key(key)? {
+            Key::Language => {
+                let language = value.to_lowercase();
+                if !LANGUAGES.contains(&language.as_str()) {
+                    bail!("Unsupported language '{}'. Supported: {}", value, LANGUAGES.join(", "));
+                }
+                self.language = Some(language);
+            }
+            Key::Mirror(tool) => {
+                self.mirrors.insert(tool, url(value)?.trim_end_matches('/').to_string());
+            }
+            Key::AutoInstall => self.auto_install = Some(parse_bool(value)?),
+            Key::CacheDir => {
+                if value.is_empty() {
+                    bail!("cache_dir cannot be empty");
+                }
+                self.cache_dir = Some(PathBuf::from(value));
+            }
+            Key::Proxy => {
+                reqwest::Proxy::all(value).map_err(|e| anyhow!("Invalid proxy '{}': {}", value, e))?;
+                self.proxy = Some(value.to_string());
+            }
+            Key::LogLevel => {
+                let level = LogLevel::parse(value)
+                    .ok_or_else(|| anyhow!("Invalid log level '{}'. Use error, warn, info or debug", value))?;
+                self.log_level = Some(level);
+            }
+        }
+        Ok(())
+    }
+
+    /// 删除某个键，返回之前是否设置过
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        Ok(match parse_key(key)? {
+            Key::Language => self.language.take().is_some(),
+            Key::Mirror(tool) => self.mirrors.remove(&tool).is_some(),
+            Key::AutoInstall => self.auto_install.take().is_some(),
+            Key::CacheDir => self.cache_dir.take().is_some(),
+            Key::Proxy => self.proxy.take().is_some(),
+            Key::LogLevel => self.log_level.take().is_some(),
+        })
+    }
+
+    /// 所有已设置的键和值
+    pub fn list(&self) -> Vec<(String, String)> {
+        let mut keys = vec!["language".to_string()];
+        keys.extend(self.mirrors.keys().map(|tool| format!("mirrors.{}", tool)));
+        keys.extend(["auto_install", "cache_dir", "proxy", "log_level"].map(str::to_string));
+        keys.into_iter()
+            .filter_map(|key| self.get(&key).ok().flatten().map(|value| (key, value)))
+            .collect()
+    }
+}
+
+enum Key {
+    Language,
+    Mirror(String),
+    AutoInstall,
+    CacheDir,
+    Proxy,
+    LogLevel,
+}
+
+fn parse_key(key: &str) -> Result<Key> {
+    Ok(match key {
+        "language" => Key::Language,
+        "auto_install" => Key::AutoInstall,
+        "cache_dir" => Key::CacheDir,
+        "proxy" => Key::Proxy,
+        "log_level" => Key::LogLevel,
+        _ => match key.strip_prefix("mirrors.") {
+            Some(tool) if downloader::is_supported(tool) => Key::Mirror(tool.to_string()),
+            Some(tool) => bail!("Unknown tool '{}' in '{}'. Supported: {}", tool, key, downloader::SUPPORTED_TOOLS.join(", ")),
+            None => bail!("Unknown setting '{}'. Available: {}", key, KEYS.join(", ")),
+        },
+    })
+}
+
+fn parse_bool(value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => bail!("Expected true or false, got '{}'", value),
+    }
+}
+
+fn url(value: &str) -> Result<&str> {
+    if value.starts_with("http://") || value.starts_with("https://") {
+        Ok(value)
+    } else {
+        bail!("Mirror must be an http:// or https:// URL, got '{}'", value)
+    }
+}
+
+/// 设置文件的路径（不能使用 EnvManPaths::new，它本身会读取设置）
+pub fn file_path() -> Result<PathBuf> {
+    Ok(EnvManPaths::detect()?.settings_file())
+}
+
+static GLOBAL: OnceLock<GlobalSettings> = OnceLock::new();
+
+/// 当前用户的全局设置（每个进程只读取一次）；文件损坏时给出警告并使用默认设置
+pub fn global() -> &'static GlobalSettings {
+    GLOBAL.get_or_init(|| {
+        let Ok(path) = file_path() else {
+            return GlobalSettings::default();
+        };
+        GlobalSettings::load(&path).unwrap_or_else(|e| {
+            eprintln!("Warning: {:#}; using default settings", e);
+            GlobalSettings::default()
+        })
+    })
+}
+
+/// 工具的下载镜像（不带结尾的 /）
+pub fn mirror(tool: &str) -> Option<&'static str> {
+    let tool = if tool == "jdk" { "java" } else { tool };
+    global().mirrors.get(tool).map(String::as_str)
+}
+
+/// 某个级别的诊断信息是否需要输出；ENMAN_LOG_LEVEL 环境变量优先于设置
+pub fn log_enabled(level: LogLevel) -> bool {
+    let configured = std::env::var("ENMAN_LOG_LEVEL")
+        .ok()
+        .and_then(|v| LogLevel::parse(&v))
+        .or(global().log_level)
+        .unwrap_or(LogLevel::Warn);
+    level <= configured
+}
+
+/// 输出警告（log_level 为 error 时不输出）
+pub fn warn(message: impl std::fmt::Display) {
+    if log_enabled(LogLevel::Warn) {
+        eprintln!("Warning: {}", message);
+    }
+}
+
+/// 输出调试信息（log_level 为 debug 时才输出）
+pub fn debug(message: impl std::fmt::Display) {
+    if log_enabled(LogLevel::Debug) {
+        eprintln!("Debug: {}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_get_unset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+
+        let mut settings = GlobalSettings::load(&path).unwrap();
+        settings.set("language", "ZH").unwrap();
+        settings.set("mirrors.node", "https://npmmirror.com/mirrors/node/").unwrap();
+        settings.set("auto_install", "yes").unwrap();
+        settings.set("log_level", "debug").unwrap();
+        assert!(settings.set("mirrors.nod", "https://example.com").is_err());
+        assert!(settings.set("mirrors.node", "ftp://example.com").is_err());
+        assert!(settings.set("language", "fr").is_err());
+        assert!(settings.set("colour", "always").is_err());
+        settings.save(&path).unwrap();
+
+        let mut loaded = GlobalSettings::load(&path).unwrap();
+        assert_eq!(loaded, settings);
+        assert_eq!(loaded.get("mirrors.node").unwrap().as_deref(), Some("https://npmmirror.com/mirrors/node"));
+        assert_eq!(loaded.get("auto_install").unwrap().as_deref(), Some("true"));
+        assert_eq!(
+            loaded.list().iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+            vec!["language", "mirrors.node", "auto_install", "log_level"]
+        );
+
+        assert!(loaded.unset("language").unwrap());
+        assert!(!loaded.unset("language").unwrap());
+        assert_eq!(loaded.get("language").unwrap(), None);
+
+        // enman init 创建的空对象
+        std::fs::write(&path, "{}").unwrap();
+        assert_eq!(GlobalSettings::load(&path).unwrap(), GlobalSettings::default());
+    }
+}
@@ -0,0 +1,401 @@
+// src/core/shim.rs
+// Shim 转发逻辑（同步），enman 和 em 两个入口共用
+use anyhow::Context;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::paths::EnvManPaths;
+use crate::core::resolve::{self, Resolution};
+use crate::core::{environment, settings, version};
+use crate::downloader;
+
+/// 开启 shim 自动安装的环境变量
+pub const AUTO_INSTALL_VAR: &str = "ENMAN_AUTO_INSTALL";
+
+/// 递归保护：shim 每次转发到系统可执行文件时加一
+///
+/// 如果 PATH 上的"系统"可执行文件其实是另一个 enman shim（例如另一份 enman 的 shims 目录），
+/// 转发会无限循环；超过上限时报错退出。
+pub const FALLBACK_DEPTH_VAR: &str = "ENMAN_SHIM_DEPTH";
+const MAX_FALLBACK_DEPTH: u32 = 8;
+
+/// 如果当前可执行文件是 shim（如 node、npm、javac），返回 shim 的名称
+pub fn shim_name() -> Option<String> {
+    let exe_name = invoked_name()?;
+    let exe_name = exe_name.as_str();
+    // enman / em 本身作为主程序运行，其他名称都视为 shim
+    if matches!(exe_name, "enman" | "em") {
+        None
+    } else {
+        Some(exe_name.to_string())
+    }
+}
+
+/// 启动当前进程时使用的命令名
+///
+/// Unix 上 shim 是指向 enman 的符号链接，而 current_exe() 会解析符号链接，
+/// 因此只能从 argv[0] 得到 shim 的名称（python3.11 这类名称中带点，保留完整文件名）；
+/// Windows 上 shim 是独立的 .exe 副本，去掉扩展名即可。
+#[cfg(unix)]
+fn invoked_name() -> Option<String> {
+    let path = PathBuf::from(env::args_os().next()?);
+    path.file_name()?.to_str().map(str::to_string)
+}
+
+#[cfg(windows)]
+fn invoked_name() -> Option<String> {
+    let path = env::current_exe().ok()?;
+    path.file_stem()?.to_str().map(str::to_string)
+}
+
+/// 可能提供某个可执行文件的工具
+///
+/// 先匹配各工具声明的可执行文件；没有匹配时查找已安装版本的 bin 目录，
+/// 这样 `npm install -g` 等方式安装的命令也能找到所属的工具。
+pub fn owning_tools(paths: &EnvManPaths, exe: &str) -> Vec<String> {
+    let declared: Vec<String> = downloader::SUPPORTED_TOOLS
+        .iter()
+        .filter(|tool| **tool == exe || downloader::executables(tool).contains(&exe))
+        .map(|tool| tool.to_string())
+        .collect();
+    if !declared.is_empty() {
+        return declared;
+    }
+
+    let Ok(entries) = fs::read_dir(&paths.installs) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|tool| {
+            paths
+                .installed_versions(tool)
+                .iter()
+                .any(|version| executables_in(&paths.install_bin_path(tool, version)).iter().any(|e| e == exe))
+        })
+        .collect()
+}
+
+/// 某个工具版本需要的所有 shim：工具声明的可执行文件，加上 bin 目录中实际存在的可执行文件
+pub fn shim_names(paths: &EnvManPaths, tool: &str, version: &str) -> Vec<String> {
+    let declared = downloader::executables(tool);
+    let mut names: Vec<String> = if declared.is_empty() {
+        vec![tool.to_string()]
+    } else {
+        declared.iter().map(|exe| exe.to_string()).collect()
+    };
+    names.extend(executables_in(&paths.install_bin_path(tool, version)));
+    // 不能让 shim 覆盖 enman 自身
+    names.retain(|name| !matches!(name.as_str(), "enman" | "em"));
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// 目录中的可执行文件名（Windows 上去掉 .exe/.cmd/.bat 扩展名）
+pub fn executables_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| executable_name(&entry.path()))
+        .collect()
+}
+
+#[cfg(unix)]
+fn executable_name(path: &Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path).ok()?.permissions().mode();
+    // python3.11 这类名称中带点，Unix 上直接使用完整文件名
+    (mode & 0o111 != 0).then(|| path.file_name()?.to_str().map(str::to_string))?
+}
+
+#[cfg(windows)]
+fn executable_name(path: &Path) -> Option<String> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    matches!(ext.as_str(), "exe" | "cmd" | "bat").then(|| path.file_stem()?.to_str().map(str::to_string))?
+}
+
+/// 解析某个可执行文件当前应转发到的工具版本
+///
+/// 多个工具提供同名可执行文件时（如 MySQL 和 MariaDB 的 mysql），优先使用生效版本中
+/// 确实包含该文件的工具。没有任何工具设置了版本时返回 None。
+pub fn resolve_executable(paths: &EnvManPaths, exe: &str) -> anyhow::Result<Option<Resolution>> {
+    let mut fallback = None;
+    for tool in owning_tools(paths, exe) {
+        if let Some(resolution) = resolve::resolve_current(paths, &tool)? {
+            if resolution.is_system() || (resolution.installed && resolution.binary(exe).exists()) {
+                return Ok(Some(resolution));
+            }
+            fallback.get_or_insert(resolution);
+        }
+    }
+    Ok(fallback)
+}
+
+/// 在 PATH 上查找系统中的可执行文件，跳过 enman 的 shims 目录以及 enman 自身
+pub fn system_executable(paths: &EnvManPaths, exe: &str) -> Option<PathBuf> {
+    let path_var = env::var_os("PATH")?;
+    let this_exe = env::current_exe().ok();
+    find_system_executable(&path_var, &paths.shims, this_exe.as_deref(), exe)
+}
+
+/// 在 path_var 中查找 exe，跳过 shims 目录和指向 this_exe（enman 本身）的文件
+fn find_system_executable(path_var: &OsStr, shims: &Path, this_exe: Option<&Path>, exe: &str) -> Option<PathBuf> {
+    let shims_dir = fs::canonicalize(shims).ok();
+    let this_exe = this_exe.and_then(|p| fs::canonicalize(p).ok());
+
+    let names: Vec<String> = if cfg!(windows) {
+        ["exe", "cmd", "bat"].iter().map(|ext| format!("{}.{}", exe, ext)).collect()
+    } else {
+        vec![exe.to_string()]
+    };
+
+    env::split_paths(path_var)
+        .filter(|dir| shims_dir.is_none() || fs::canonicalize(dir).ok() != shims_dir)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .filter(|candidate| candidate.is_file() && executable_name(candidate).is_some())
+        // 递归保护：指向 enman 自身的链接（或就是当前这个 shim）不算系统可执行文件
+        .find(|candidate| this_exe.is_none() || fs::canonicalize(candidate).ok() != this_exe)
+}
+
+/// 回退到系统可执行文件时传给子进程的 ENMAN_SHIM_DEPTH；current 为当前的值，
+/// 已经达到上限（PATH 上另有一份 enman shim 导致循环）时返回 None
+fn next_fallback_depth(current: Option<&str>) -> Option<u32> {
+    let depth: u32 = current.and_then(|d| d.parse().ok()).unwrap_or(0);
+    (depth < MAX_FALLBACK_DEPTH).then_some(depth + 1)
+}
+
+/// 去掉 shims 目录后的 PATH，系统可执行文件启动的子进程不会再回到 enman
+fn path_without_shims(paths: &EnvManPaths) -> Option<std::ffi::OsString> {
+    let path_var = env::var_os("PATH")?;
+    let shims_dir = fs::canonicalize(&paths.shims).ok();
+    let dirs = env::split_paths(&path_var).filter(|dir| shims_dir.is_none() || fs::canonicalize(dir).ok() != shims_dir);
+    env::join_paths(dirs).ok()
+}
+
+/// 执行系统中的可执行文件；PATH 上找不到时返回，由调用方报告错误
+fn run_system(paths: &EnvManPaths, exe: &str, args: &[String]) -> anyhow::Result<()> {
+    let Some(depth) = next_fallback_depth(env::var(FALLBACK_DEPTH_VAR).ok().as_deref()) else {
+        eprintln!("Error: shim recursion detected while looking for a system '{}'.", exe);
+        eprintln!("Another directory on PATH seems to contain enman shims; remove it or install {} with enman.", exe);
+        std::process::exit(1);
+    };
+
+    let Some(system_bin) = system_executable(paths, exe) else {
+        return Ok(());
+    };
+    let mut command = std::process::Command::new(&system_bin);
+    command.args(args).env(FALLBACK_DEPTH_VAR, depth.to_string());
+    if let Some(path) = path_without_shims(paths) {
+        command.env("PATH", path);
+    }
+    exec(command, exe)
+}
+
+/// 用目标程序替换当前进程（Unix），或等待其退出并返回相同的退出码（Windows）
+pub fn exec(mut command: std::process::Command, exe: &str) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let error = command.exec();
+        eprintln!("Failed to execute {}: {}", exe, error);
+        std::process::exit(1);
+    }
+
+    #[cfg(windows)]
+    {
+        let status = command
+            .status()
+            .map_err(|e| anyhow::anyhow!("Failed to execute {}: {}", exe, e))?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+}
+
+/// 生效版本未安装时是否自动安装
+///
+/// 环境变量 ENMAN_AUTO_INSTALL（1/true/yes 开启，0/false/no 关闭）优先，
+/// 其次是 .enmanrc 中的 `[settings] auto_install = true`，最后是全局设置 auto_install。默认关闭。
+pub fn auto_install_enabled(cwd: &Path) -> bool {
+    if let Ok(value) = env::var(AUTO_INSTALL_VAR) {
+        match value.trim().to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => return true,
+            "0" | "false" | "no" | "off" => return false,
+            _ => {}
+        }
+    }
+    resolve::enmanrc_setting(cwd, |settings| settings.auto_install)
+        .or(settings::global().auto_install)
+        .unwrap_or(false)
+}
+
+/// 安装 shim 缺失的版本
+///
+/// 只在需要时创建 tokio 运行时。多个 shim 进程同时缺失同一版本时（如并行的构建脚本），
+/// 通过 installs/<tool>/.locks/<version>.lock 文件锁保证只安装一次，其余进程等待安装完成。
+pub fn auto_install(paths: &EnvManPaths, resolution: &Resolution) -> anyhow::Result<()> {
+    let tool = &resolution.tool;
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let version = runtime.block_on(version::resolve(paths, tool, &resolution.requested))?;
+
+    let lock_dir = paths.install_dir(tool).join(".locks");
+    fs::create_dir_all(&lock_dir)?;
+    let lock_path = lock_dir.join(format!("{}.lock", version));
+    let lock = fs::File::create(&lock_path)?;
+    lock.lock().with_context(|| format!("Failed to lock {}", lock_path.display()))?;
+
+    // 拿到锁之后再检查一次：其他进程可能已经装好了
+    let install_path = paths.install_dir(tool).join(&version);
+    if !install_path.is_dir() {
+        eprintln!("enman: {}@{} is not installed, installing {} (set by {})", tool, resolution.requested, version, resolution.source);
+        runtime.block_on(crate::cli::install::ensure_installed(paths, tool, &version))?;
+    }
+    Ok(())
+}
+
+pub fn run_tool(exe: &str, args: &[String]) -> anyhow::Result<()> {
+    let paths = EnvManPaths::new()?;
+
+    let mut resolution = resolve_executable(&paths, exe)?;
+    if let Some(missing) = resolution.as_ref().filter(|r| !r.installed) {
+        if auto_install_enabled(&env::current_dir()?) {
+            auto_install(&paths, missing)?;
+            resolution = resolve_executable(&paths, exe)?;
+        }
+    }
+
+    let Some(resolution) = resolution else {
+        // 没有设置任何版本：回退到系统中的同名可执行文件
+        run_system(&paths, exe, args)?;
+        match owning_tools(&paths, exe).first() {
+            Some(tool) => {
+                eprintln!("Error: no global version set for '{}', and no system '{}' was found on PATH.", tool, exe);
+                eprintln!("Run: enman global {}@<version>", tool);
+            }
+            None => eprintln!("Error: no installed tool provides '{}'.", exe),
+        }
+        std::process::exit(1);
+    };
+
+    if resolution.is_system() {
+        run_system(&paths, exe, args)?;
+        eprintln!("Error: {} is set to 'system' by {}, but no system '{}' was found on PATH.", resolution.tool, resolution.source, exe);
+        std::process::exit(1);
+    }
+
+    let tool_bin = resolution.binary(exe);
+    if !resolution.installed {
+        eprintln!(
+            "Error: {}@{} is not installed (looked for {})",
+            resolution.tool,
+            resolution.requested,
+            tool_bin.display()
+        );
+        eprintln!("Version set by {}", resolution.source);
+        eprintln!("Run: enman install {}@{}", resolution.tool, resolution.requested);
+        eprintln!("Or set {}=1 to install missing versions automatically", AUTO_INSTALL_VAR);
+        std::process::exit(1);
+    }
+    if !tool_bin.exists() {
+        eprintln!(
+            "Error: {}@{} does not provide '{}' (looked for {})",
+            resolution.tool,
+            resolution.version,
+            exe,
+            tool_bin.display()
+        );
+        eprintln!("Version set by {}", resolution.source);
+        std::process::exit(1);
+    }
+
+    // 🚀 执行工具，带上 .enmanrc 中定义的项目变量
+    let cwd = env::current_dir()?;
+    let layered = resolve::layered_config(&cwd);
+    let vars = environment::project_vars(&paths, &layered, |tool| {
+        if tool == resolution.tool {
+            return Some(resolution.version.clone());
+        }
+        resolve::resolve(&paths, tool, &cwd)
+            .filter(|r| r.installed && !r.is_system())
+            .map(|r| r.version)
+    });
+    let mut command = std::process::Command::new(&tool_bin);
+    command.args(args).envs(vars);
+    exec(command, exe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owning_tools() {
+        let root = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(root.path().to_path_buf());
+
+        assert_eq!(owning_tools(&paths, "npm"), vec!["node"]);
+        assert_eq!(owning_tools(&paths, "javac"), vec!["java"]);
+        assert_eq!(owning_tools(&paths, "mysql"), vec!["mysql", "mariadb"]);
+        assert!(owning_tools(&paths, "yarn").is_empty());
+
+        // npm install -g 安装到 node 的 bin 目录中的命令
+        let bin = paths.install_bin_path("node", "18.17.0");
+        fs::create_dir_all(&bin).unwrap();
+        let yarn = if cfg!(windows) { bin.join("yarn.cmd") } else { bin.join("yarn") };
+        fs::write(&yarn, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&yarn, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        assert_eq!(owning_tools(&paths, "yarn"), vec!["node"]);
+        assert!(shim_names(&paths, "node", "18.17.0").contains(&"yarn".to_string()));
+    }
+
+    #[test]
+    fn test_system_executable_skips_shims_and_enman() {
+        let root = tempfile::tempdir().unwrap();
+        let exe_name = |name: &str| if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() };
+        let executable = |dir: &str, name: &str| {
+            let dir = root.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            let path = dir.join(exe_name(name));
+            fs::write(&path, "").unwrap();
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+            }
+            path
+        };
+
+        let shims = executable("shims", "node").parent().unwrap().to_path_buf();
+        let enman = executable("enman-bin", "enman");
+        // PATH 上另一个目录中指向 enman 的链接（如另一份 shims 目录）
+        let linked = root.path().join("other-shims");
+        fs::create_dir_all(&linked).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&enman, linked.join("node")).unwrap();
+        let system = executable("usr-bin", "node");
+
+        let path_var = env::join_paths([&shims, &linked, &root.path().join("usr-bin")]).unwrap();
+        assert_eq!(find_system_executable(&path_var, &shims, Some(&enman), "node"), Some(system));
+
+        let path_var = env::join_paths([&shims, &linked]).unwrap();
+        assert_eq!(find_system_executable(&path_var, &shims, Some(&enman), "node"), None);
+    }
+
+    #[test]
+    fn test_fallback_depth_limit() {
+        assert_eq!(next_fallback_depth(None), Some(1));
+        assert_eq!(next_fallback_depth(Some("not a number")), Some(1));
+        assert_eq!(next_fallback_depth(Some("3")), Some(4));
+        assert_eq!(next_fallback_depth(Some(&(MAX_FALLBACK_DEPTH - 1).to_string())), Some(MAX_FALLBACK_DEPTH));
+        assert_eq!(next_fallback_depth(Some(&MAX_FALLBACK_DEPTH.to_string())), None);
+    }
+}
@@ -0,0 +1,137 @@
+// src/core/shim_manager.rs
+// 管理 ~/.enman/shims 目录：Unix 上 shim 是指向 enman 的符号链接，
+// Windows 上是 enman.exe 的副本（以 .exe 结尾才能被 PATH 找到）
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::paths::EnvManPaths;
+use crate::core::shim;
+
+/// reshim 的结果
+#[derive(Debug, Default)]
+pub struct ReshimReport {
+    pub created: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// shim 文件的路径：Unix 上与命令同名，Windows 上加 .exe
+pub fn shim_path(paths: &EnvManPaths, name: &str) -> PathBuf {
+    if cfg!(windows) {
+        paths.shims.join(format!("{}.exe", name))
+    } else {
+        paths.shims.join(name)
+    }
+}
+
+/// 为工具某个版本的所有可执行文件创建 shim，返回 shim 名称
+pub fn create_shims(paths: &EnvManPaths, tool: &str, version: &str) -> Result<Vec<String>> {
+    let enman = std::env::current_exe()?;
+    let names = shim::shim_names(paths, tool, version);
+    for name in &names {
+        create_shim(paths, &enman, name)?;
+    }
+    Ok(names)
+}
+
+/// 创建（或替换）单个 shim
+pub fn create_shim(paths: &EnvManPaths, enman: &Path, name: &str) -> Result<()> {
+    fs::create_dir_all(&paths.shims)?;
+    let path = shim_path(paths, name);
+    if path.symlink_metadata().is_ok() {
+        fs::remove_file(&path).with_context(|| format!("Failed to replace shim {}", path.display()))?;
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(enman, &path)
+        .with_context(|| format!("Failed to create shim {}", path.display()))?;
+
+    #[cfg(windows)]
+    fs::copy(enman, &path).with_context(|| format!("Failed to create shim {}", path.display()))?;
+
+    Ok(())
+}
+
+/// 所有已安装版本需要的 shim
+pub fn expected_shims(paths: &EnvManPaths) -> BTreeSet<String> {
+    let mut names = BTreeSet::new();
+    let Ok(entries) = fs::read_dir(&paths.installs) else {
+        return names;
+    };
+    for tool in entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().into_owned()) {
+        for version in paths.installed_versions(&tool) {
+            names.extend(shim::shim_names(paths, &tool, &version));
+        }
+    }
+    names
+}
+
+/// shims 目录中现有的 shim 名称
+pub fn existing_shims(paths: &EnvManPaths) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(&paths.shims) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            if cfg!(windows) {
+                let is_exe = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("exe"));
+                is_exe.then(|| path.file_stem()?.to_str().map(str::to_string))?
+            } else {
+                path.file_name()?.to_str().map(str::to_string)
+            }
+        })
+        .collect()
+}
+
+/// 删除不再有任何已安装版本提供的 shim，返回被删除的名称
+pub fn remove_orphans(paths: &EnvManPaths) -> Result<Vec<String>> {
+    let expected = expected_shims(paths);
+    let mut removed = Vec::new();
+    for name in existing_shims(paths) {
+        if !expected.contains(&name) {
+            fs::remove_file(shim_path(paths, &name))?;
+            removed.push(name);
+        }
+    }
+    removed.sort();
+    Ok(removed)
+}
+
+/// 根据已安装的版本重建所有 shim，并删除孤立的 shim
+pub fn reshim(paths: &EnvManPaths) -> Result<ReshimReport> {
+    let enman = std::env::current_exe()?;
+    let mut report = ReshimReport::default();
+    for name in expected_shims(paths) {
+        create_shim(paths, &enman, &name)?;
+        report.created.push(name);
+    }
+    report.removed = remove_orphans(paths)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reshim_removes_orphans() {
+        let root = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(root.path().to_path_buf());
+        fs::create_dir_all(paths.install_bin_path("redis", "7.2.4")).unwrap();
+        fs::create_dir_all(&paths.shims).unwrap();
+        fs::write(shim_path(&paths, "npm"), "").unwrap();
+
+        let report = reshim(&paths).unwrap();
+        assert!(report.created.contains(&"redis-cli".to_string()));
+        assert_eq!(report.removed, vec!["npm"]);
+
+        let shims = existing_shims(&paths);
+        assert!(shims.contains(&"redis-server".to_string()));
+        assert!(!shims.contains(&"npm".to_string()));
+        #[cfg(unix)]
+        assert!(shim_path(&paths, "redis-cli").symlink_metadata().unwrap().file_type().is_symlink());
+    }
+}
@@ -0,0 +1,50 @@
+// src/core/testing.rs
+// 测试用的临时项目目录：enman 数据目录和项目文件都放在同一个临时目录中，测试结束时删除
+use std::path::{Path, PathBuf};
+
+use crate::core::paths::EnvManPaths;
+
+pub struct Fixture {
+    tmp: tempfile::TempDir,
+    /// 以临时目录下的 .enman 为根目录
+    pub paths: EnvManPaths,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = EnvManPaths::from_root(tmp.path().join(".enman"));
+        Self { tmp, paths }
+    }
+
+    /// 临时目录本身
+    pub fn root(&self) -> &Path {
+        self.tmp.path()
+    }
+
+    /// 创建临时目录中的子目录（包括上级目录），返回其路径
+    pub fn dir(&self, relative: &str) -> PathBuf {
+        let dir = self.tmp.path().join(relative);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 写入临时目录中的文件（自动创建上级目录），返回其路径
+    pub fn write(&self, relative: &str, content: &str) -> PathBuf {
+        let file = self.tmp.path().join(relative);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, content).unwrap();
+        file
+    }
+}
+
+/// 不包含任何变量的环境，传给 `resolve::candidates_with` 等读取环境变量的函数，
+/// 使测试不受开发者 shell 中 ENMAN_* 变量的影响
+pub fn no_env(_: &str) -> Option<String> {
+    None
+}
+
+/// 只包含给定变量的环境
+pub fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    |name| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+}
@@ -1,5 +1,9 @@
+use anyhow::{anyhow, Result};
 use semver::{Version, VersionReq};
 
+use crate::core::paths::EnvManPaths;
+use crate::core::settings;
+
 /// 比较两个版本字符串
 /// 返回值：Ordering::Greater 如果 a > b
 ///         Ordering::Less 如果 a < b
@@ -18,6 +22,179 @@ pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
     }
 }
 
+/// 特殊版本值：使用系统中（PATH 上 enman shim 之外）已有的可执行文件
+pub const SYSTEM: &str = "system";
+
+pub fn is_system(requested: &str) -> bool {
+    requested.trim().eq_ignore_ascii_case(SYSTEM)
+}
+
+/// 用户在命令行或配置文件中写下的版本要求
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionSpec {
+    /// 完整版本号（如 18.17.0），或无法识别为范围的字符串，原样使用
+    Exact(String),
+    /// 部分版本（18、3.11）或 semver 范围（^18.2、>=3.10, <3.12）
+    Req(VersionReq),
+    /// 最新的正式版本
+    Latest,
+    /// 最新的 LTS 版本
+    Lts,
+}
+
+impl VersionSpec {
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.to_lowercase().as_str() {
+            "latest" | "current" | "stable" => return Ok(Self::Latest),
+            "lts" => return Ok(Self::Lts),
+            _ => {}
+        }
+
+        let bare = s.strip_prefix('v').unwrap_or(s);
+        let parts: Vec<&str> = bare.split('.').collect();
+        let all_numeric = parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()));
+
+        // 18 / 3.11：部分版本，匹配该前缀下的所有版本
+        if all_numeric && parts.len() < 3 {
+            let req = VersionReq::parse(&format!("={}", bare))
+                .map_err(|e| anyhow!("Invalid version '{}': {}", s, e))?;
+            return Ok(Self::Req(req));
+        }
+
+        // 18.17.0 / 3.12.0rc1 等：精确版本
+        if Version::parse(bare).is_ok() || !looks_like_range(bare) {
+            return Ok(Self::Exact(bare.to_string()));
+        }
+
+        VersionReq::parse(bare)
+            .map(Self::Req)
+            .map_err(|e| anyhow!("Invalid version range '{}': {}", s, e))
+    }
+}
+
+fn looks_like_range(s: &str) -> bool {
+    s.contains(['^', '~', '<', '>', '=', '*', ','])
+        || s.split('.').any(|p| p.eq_ignore_ascii_case("x"))
+}
+
+/// 宽松解析版本号：允许 v 前缀，缺失的次版本号和修订号补 0（17 → 17.0.0）
+pub fn parse_lenient(v: &str) -> Option<Version> {
+    let v = v.trim().strip_prefix('v').unwrap_or(v.trim());
+    if let Ok(version) = Version::parse(v) {
+        return Some(version);
+    }
+    let parts: Vec<&str> = v.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    let mut numbers = [0u64; 3];
+    for (i, part) in parts.iter().enumerate() {
+        numbers[i] = part.parse().ok()?;
+    }
+    Some(Version::new(numbers[0], numbers[1], numbers[2]))
+}
+
+/// 工具是否有 LTS 发布线
+pub fn has_lts_line(tool: &str) -> bool {
+    matches!(tool, "node" | "java" | "jdk")
+}
+
+/// 判断某个版本是否属于 LTS 发布线
+///
+/// - Node.js：偶数主版本（4 及以上）进入 LTS
+/// - Java：8、11，以及从 17 开始每隔 4 个特性版本
+pub fn is_lts(tool: &str, version: &Version) -> bool {
+    match tool {
+        "node" => version.major >= 4 && version.major.is_multiple_of(2),
+        "java" | "jdk" => match version.major {
+            8 | 11 => true,
+            m if m >= 17 => (m - 17).is_multiple_of(4),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// 从候选版本中选出满足要求的最高版本，返回候选版本的原始写法
+pub fn select<'a, I>(tool: &str, spec: &VersionSpec, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let parsed = candidates
+        .into_iter()
+        .filter_map(|c| parse_lenient(c).map(|v| (v, c)));
+
+    let best = match spec {
+        VersionSpec::Exact(exact) => {
+            return Some(exact.clone());
+        }
+        VersionSpec::Req(req) => parsed.filter(|(v, _)| req.matches(v)).max_by(|a, b| a.0.cmp(&b.0)),
+        VersionSpec::Latest => parsed.filter(|(v, _)| v.pre.is_empty()).max_by(|a, b| a.0.cmp(&b.0)),
+        VersionSpec::Lts => parsed
+            .filter(|(v, _)| v.pre.is_empty() && is_lts(tool, v))
+            .max_by(|a, b| a.0.cmp(&b.0)),
+    };
+
+    best.map(|(_, c)| c.strip_prefix('v').unwrap_or(c).to_string())
+}
+
+/// 仅根据已安装的版本解析版本要求（供 shim 等不能访问网络的场景使用）
+pub fn resolve_installed(paths: &EnvManPaths, tool: &str, requested: &str) -> Option<String> {
+    let spec = VersionSpec::parse(requested).ok()?;
+    let installed = paths.installed_versions(tool);
+    select(tool, &spec, installed.iter().map(String::as_str))
+}
+
+/// 将用户请求的版本解析为精确版本
+///
+/// 当前目录的 .enman.lock 中锁定的版本满足要求时，直接使用锁定的版本；
+/// 否则见 `resolve_unlocked`。
+pub async fn resolve(paths: &EnvManPaths, tool: &str, requested: &str) -> Result<String> {
+    if let Some(locked) = crate::core::lock::locked(tool) {
+        if satisfies(tool, requested, &locked.version) {
+            return Ok(locked.version);
+        }
+    }
+    resolve_unlocked(paths, tool, requested).await
+}
+
+/// 精确版本 version 是否满足版本要求 requested
+pub fn satisfies(tool: &str, requested: &str, version: &str) -> bool {
+    match VersionSpec::parse(requested) {
+        Ok(VersionSpec::Exact(exact)) => exact == version.strip_prefix('v').unwrap_or(version),
+        Ok(spec) => select(tool, &spec, [version]).is_some(),
+        Err(_) => false,
+    }
+}
+
+/// 不考虑锁文件，将用户请求的版本解析为精确版本
+///
+/// 精确版本直接返回；部分版本、范围以及 latest/lts 别名会同时与已安装版本
+/// 和远程可用版本比对，取满足条件的最高版本。远程列表获取失败时只使用已安装版本。
+pub async fn resolve_unlocked(paths: &EnvManPaths, tool: &str, requested: &str) -> Result<String> {
+    let spec = VersionSpec::parse(requested)?;
+    if let VersionSpec::Exact(exact) = spec {
+        return Ok(exact);
+    }
+    if spec == VersionSpec::Lts && !has_lts_line(tool) {
+        return Err(anyhow!("'{}' has no LTS release line", tool));
+    }
+
+    let installed = paths.installed_versions(tool);
+    let available = match crate::downloader::list_available_versions(tool, None).await {
+        Ok(versions) => versions,
+        Err(e) => {
+            settings::warn(format_args!("could not fetch remote {} versions ({}), using installed versions only", tool, e));
+            Vec::new()
+        }
+    };
+
+    let candidates = installed.iter().chain(available.iter()).map(String::as_str);
+    select(tool, &spec, candidates)
+        .ok_or_else(|| anyhow!("No version of {} matches '{}'", tool, requested))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -31,4 +208,29 @@ mod tests {
         assert_eq!(compare_versions("1.0.0", "1.0.0"), Ordering::Equal);
         assert_eq!(compare_versions("2.0.0", "1.9.9"), Ordering::Greater);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_select_versions() {
+        let node = ["20.11.0", "18.19.1", "18.17.0", "19.9.0", "21.0.0-rc.1"];
+        let pick = |s: &str| select("node", &VersionSpec::parse(s).unwrap(), node.iter().copied());
+
+        assert_eq!(pick("18").as_deref(), Some("18.19.1"));
+        assert_eq!(pick("18.17").as_deref(), Some("18.17.0"));
+        assert_eq!(pick("^18.18").as_deref(), Some("18.19.1"));
+        assert_eq!(pick(">=19, <20").as_deref(), Some("19.9.0"));
+        assert_eq!(pick("latest").as_deref(), Some("20.11.0"));
+        assert_eq!(pick("lts").as_deref(), Some("20.11.0"));
+        assert_eq!(pick("v16.20.2").as_deref(), Some("16.20.2"));
+        assert_eq!(pick("17"), None);
+
+        let java = ["21", "17", "11"];
+        let spec = VersionSpec::parse("17").unwrap();
+        assert_eq!(select("java", &spec, java.iter().copied()).as_deref(), Some("17"));
+        assert_eq!(select("python", &VersionSpec::Lts, ["3.12.1"].iter().copied()), None);
+
+        assert!(satisfies("node", "18", "18.17.0"));
+        assert!(satisfies("node", "v18.17.0", "18.17.0"));
+        assert!(!satisfies("node", "18.19.1", "18.17.0"));
+        assert!(!satisfies("node", "^20", "18.17.0"));
+    }
+}
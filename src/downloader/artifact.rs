@@ -0,0 +1,88 @@
+// src/downloader/artifact.rs
+// 记录和校验下载的安装包（下载地址 + SHA-256），供 .enman.lock 使用
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// 安装回执文件，安装成功后写入版本目录
+pub const RECEIPT_FILE: &str = ".enman-install.toml";
+
+/// 一次安装实际使用的安装包
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub url: String,
+    pub sha256: String,
+}
+
+/// 计算文件的 SHA-256（小写十六进制）
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 计算下载文件的哈希；如果有锁定的安装包，哈希不一致时返回错误
+pub fn verify(url: &str, archive: &Path, pin: Option<&Artifact>) -> Result<Artifact> {
+    let sha256 = sha256_file(archive)?;
+    if let Some(pin) = pin {
+        if !pin.sha256.eq_ignore_ascii_case(&sha256) {
+            bail!(
+                "Checksum mismatch for {}\n  expected (.enman.lock): {}\n  actual:                 {}",
+                url,
+                pin.sha256,
+                sha256
+            );
+        }
+    }
+    Ok(Artifact { url: url.to_string(), sha256 })
+}
+
+/// 有锁定安装包时只使用锁定的下载地址，否则使用工具自己的下载地址列表
+pub fn download_urls(pin: Option<&Artifact>, default_urls: Vec<String>) -> Vec<String> {
+    match pin {
+        Some(pin) => vec![pin.url.clone()],
+        None => default_urls,
+    }
+}
+
+pub fn write_receipt(install_dir: &Path, artifact: &Artifact) -> Result<()> {
+    fs::write(install_dir.join(RECEIPT_FILE), toml::to_string(artifact)?)?;
+    Ok(())
+}
+
+pub fn read_receipt(install_dir: &Path) -> Option<Artifact> {
+    let content = fs::read_to_string(install_dir.join(RECEIPT_FILE)).ok()?;
+    toml::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_rejects_checksum_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("node.tar.xz");
+        fs::write(&archive, b"hello").unwrap();
+        let url = "https://example.com/node.tar.xz";
+
+        let artifact = verify(url, &archive, None).unwrap();
+        assert_eq!(artifact.sha256, "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824");
+        assert!(verify(url, &archive, Some(&artifact)).is_ok());
+
+        let tampered = Artifact { sha256: "0".repeat(64), ..artifact };
+        let err = verify(url, &archive, Some(&tampered)).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+}
@@ -7,6 +7,14 @@ use std::fs;
 use tempfile::TempDir;
 use tokio::io::AsyncWriteExt;
 
+use super::artifact::{self, Artifact};
+
+/// JDK 中常用的可执行文件，每个都会创建 shim
+pub const EXECUTABLES: &[&str] = &[
+    "java", "javac", "jar", "javadoc", "javap", "jshell", "jlink", "jpackage",
+    "jcmd", "jconsole", "jdb", "jps", "jstack", "jstat", "jmap", "keytool",
+];
+
 fn detect_platform() -> Result<(&'static str, &'static str)> {
     let os = if cfg!(windows) {
         "windows"
@@ -41,7 +49,7 @@ async fn get_download_url(version: &str, os: &str, arch: &str) -> Result<String>
 
     eprintln!("   → Fetching release metadata from: {}", metadata_url);
 
-    let client = reqwest::Client::builder()
+    let client = super::client_builder()
         .timeout(std::time::Duration::from_secs(15))
         .build()
         .context("Failed to build HTTP client")?;
@@ -64,24 +72,18 @@ async fn get_download_url(version: &str, os: &str, arch: &str) -> Result<String>
         .as_str()
         .ok_or_else(|| anyhow::anyhow!("'package.name' not found"))?;
 
-    // Step 2: 构造 TUNA 镜像 URL（使用标准目录结构）
+    // Step 2: 构造镜像 URL（使用标准目录结构），默认使用 TUNA 镜像
     // TUNA 路径: /17/jdk/x64/windows/filename.zip
-    let tuna_url = format!(
-        "https://mirrors.tuna.tsinghua.edu.cn/Adoptium/{}/{}/{}/{}/{}",
-        version,
-        "jdk",
-        arch,
-        os,
-        filename
-    );
+    let mirror = crate::core::settings::mirror("java").unwrap_or("https://mirrors.tuna.tsinghua.edu.cn/Adoptium");
+    let mirror_url = format!("{}/{}/{}/{}/{}/{}", mirror, version, "jdk", arch, os, filename);
 
-    eprintln!("   → Using TUNA mirror: {}", tuna_url);
-    Ok(tuna_url)
+    eprintln!("   → Using mirror: {}", mirror_url);
+    Ok(mirror_url)
 }
 async fn download_with_progress(url: &str, dest: &Path) -> Result<()> {
     eprintln!("   → Downloading (with retry)...");
 
-    let client = reqwest::Client::builder()
+    let client = super::client_builder()
         .timeout(std::time::Duration::from_secs(300)) // 5分钟超时
         .connect_timeout(std::time::Duration::from_secs(30))
         .user_agent(concat!(
@@ -186,7 +188,7 @@ fn extract_and_flatten(archive: &Path, dest: &Path, _is_zip: bool) -> Result<()>
     Ok(())
 }
 
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
     let java_bin = if cfg!(windows) {
         install_dir.join("bin").join("java.exe")
     } else {
@@ -195,19 +197,29 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
 
     if java_bin.exists() {
         println!("⚠️  Java {} already installed at {}", version, install_dir.display());
-        return Ok(());
+        return Ok(None);
     }
 
     let (os, arch) = detect_platform()?;
 
-    eprintln!("🔍 Fetching download URL for Java {} ({}, {})...", version, os, arch);
-    let download_url = get_download_url(version, os, arch).await?;
+    // 锁文件中已记录下载地址时，不再查询 Adoptium API
+    let download_url = match pin {
+        Some(pin) => {
+            eprintln!("🔒 Using locked download URL for Java {}", version);
+            pin.url.clone()
+        }
+        None => {
+            eprintln!("🔍 Fetching download URL for Java {} ({}, {})...", version, os, arch);
+            get_download_url(version, os, arch).await?
+        }
+    };
 
     let temp_dir = TempDir::new()?;
     let archive_path = temp_dir.path().join("jdk.zip");
 
     download_with_progress(&download_url, &archive_path).await
         .context("Failed to download JDK")?;
+    let artifact = artifact::verify(&download_url, &archive_path, pin)?;
 
     extract_and_flatten(&archive_path, install_dir, true)
         .context("Failed to extract JDK")?;
@@ -220,14 +232,14 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
     }
 
     eprintln!("✨ Java {} installed successfully to {}", version, install_dir.display());
-    Ok(())
+    Ok(Some(artifact))
 }
 
 // 获取 Java 可用版本的函数
 pub async fn list_available_versions(limit: Option<usize>) -> Result<Vec<String>> {
     // 从 Adoptium API 获取所有可用的 Java 版本
     let url = "https://api.adoptium.net/v3/info/available_releases";
-    let response = reqwest::get(url).await?;
+    let response = super::http_client()?.get(url).send().await?;
     
     if !response.status().is_success() {
         return Err(anyhow::anyhow!("Failed to fetch Java versions list"));
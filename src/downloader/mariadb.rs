@@ -4,6 +4,14 @@ use indicatif::{ProgressBar, ProgressStyle};
 use std::path::Path;
 use std::fs;
 use tempfile::TempDir;
+
+use super::artifact::{self, Artifact};
+
+/// MariaDB 发行包中的程序；mysql 等兼容名称与 MySQL 相同，由当前生效的版本决定使用哪一个
+pub const EXECUTABLES: &[&str] = &[
+    "mariadb", "mariadbd", "mariadb-admin", "mariadb-dump",
+    "mysql", "mysqld", "mysqladmin", "mysqldump",
+];
 use tokio::io::AsyncWriteExt;
 use std::process::Command;
 
@@ -58,6 +66,12 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
     
     // MariaDB is open-source and freely downloadable
     let mut urls = Vec::new();
+    // 镜像使用 archive.mariadb.org 相同的目录结构
+    let mirror_path = if os == "win" {
+        format!("mariadb-{}/{}/{}", version, platform_str, filename)
+    } else {
+        format!("mariadb-{}/bintar-{}/{}", version, platform_str, filename)
+    };
     
     if os == "win" {
         // Specific URLs for Windows - using the correct path structure
@@ -78,11 +92,11 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
         ]);
     }
     
-    Ok(urls)
+    Ok(super::with_mirror("mariadb", &mirror_path, urls))
 }
 
 async fn download_with_progress(url: &str, dest: &Path) -> Result<()> {
-    let res = reqwest::get(url).await.context("Failed to start download")?;
+    let res = super::http_client()?.get(url).send().await.context("Failed to start download")?;
     if !res.status().is_success() {
         return Err(anyhow::anyhow!("HTTP {}: {}", res.status(), url));
     }
@@ -177,7 +191,7 @@ fn extract_and_flatten(archive: &Path, dest: &Path, format: &str) -> Result<()>
     Ok(())
 }
 
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
     // Check if MariaDB is already installed
     let mariadb_bin = if cfg!(windows) {
         install_dir.join("bin").join("mysqld.exe")
@@ -187,17 +201,18 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
 
     if mariadb_bin.exists() {
         println!("⚠️  MariaDB {} already installed", version);
-        return Ok(());
+        return Ok(None);
     }
 
     let (_os, _arch, ext) = detect_platform()?;
-    let urls = get_download_urls(version)?;
+    let urls = artifact::download_urls(pin, get_download_urls(version)?);
     let temp_dir = TempDir::new()?;
     let archive_path = temp_dir.path().join(format!("mariadb.{}", ext));
 
-    let mut success = false;
+    let mut used_url = None;
     for (i, url) in urls.iter().enumerate() {
         let source = match i {
+            _ if pin.is_some() => "Locked",
             0 => "Tsinghua Mirror",
             1 => "MariaDB Archive",
             2 => "NLUUG FTP",
@@ -207,29 +222,30 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
         
         if download_with_progress(url, &archive_path).await.is_ok() {
             eprintln!("✅ Using source: {}", source);
-            success = true;
+            used_url = Some(url);
             break;
         } else {
             eprintln!("⚠️  [{}] Failed", source);
         }
     }
 
-    if !success {
+    let Some(used_url) = used_url else {
         eprintln!("❌ Unable to automatically download MariaDB.");
-        eprintln!("");
+        eprintln!();
         eprintln!("💡 Manual installation steps:");
         eprintln!("   1. Visit: https://mariadb.org/download/");
         eprintln!("   2. Download MariaDB Server for your OS");
         eprintln!("   3. Extract to: {}", install_dir.display());
         eprintln!("   4. Run: enman global mariadb@{}", version);
-        eprintln!("");
+        eprintln!();
         eprintln!("🔄 Alternatively, try installing a different version:");
         eprintln!("   enman install mariadb@11.4.2");
-        eprintln!("");
+        eprintln!();
         eprintln!("📋 MariaDB is fully compatible with MySQL commands and syntax.");
-        eprintln!("");
+        eprintln!();
         return Err(anyhow::anyhow!("Automatic download failed. See instructions above."));
-    }
+    };
+    let artifact = artifact::verify(used_url, &archive_path, pin)?;
 
     extract_and_flatten(&archive_path, install_dir, ext)
         .context("Failed to extract MariaDB")?;
@@ -243,7 +259,7 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
     // Initialize MariaDB data directory and get temporary root password
     initialize_mariadb(install_dir, version).await?;
     
-    Ok(())
+    Ok(Some(artifact))
 }
 
 async fn initialize_mariadb(install_dir: &Path, _version: &str) -> Result<()> {
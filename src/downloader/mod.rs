@@ -1,4 +1,5 @@
 // src/downloader/mod.rs
+pub mod artifact;
 pub mod java;
 pub mod mariadb;
 pub mod mysql;
@@ -6,17 +7,78 @@ pub mod node;
 pub mod python;
 pub mod redis;  // 添加Redis模块
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::path::Path;
 
-pub async fn install(tool: &str, version: &str, install_dir: &Path) -> Result<()> {
+use crate::core::paths::EnvManPaths;
+use crate::core::settings;
+use artifact::Artifact;
+
+/// enman 可以安装的工具
+pub const SUPPORTED_TOOLS: &[&str] = &["node", "java", "python", "mysql", "mariadb", "redis"];
+
+/// 可以由 `enman config apply` 启动的服务
+pub const SERVICES: &[&str] = &["mysql", "mariadb", "redis"];
+
+/// 工具是否可以安装（jdk 是 java 的别名）
+pub fn is_supported(tool: &str) -> bool {
+    SUPPORTED_TOOLS.contains(&tool) || tool == "jdk"
+}
+
+/// 下载使用的 HTTP 客户端配置，应用全局设置中的代理（`enman config set --global proxy`）
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    match settings::global().proxy.as_deref().map(reqwest::Proxy::all) {
+        Some(Ok(proxy)) => {
+            settings::debug(format_args!("using proxy {}", settings::global().proxy.as_deref().unwrap_or_default()));
+            builder.proxy(proxy)
+        }
+        Some(Err(e)) => {
+            settings::warn(format_args!("ignoring invalid proxy setting: {}", e));
+            builder
+        }
+        None => builder,
+    }
+}
+
+/// 使用默认配置的 HTTP 客户端
+pub fn http_client() -> Result<reqwest::Client> {
+    client_builder().build().context("Failed to build HTTP client")
+}
+
+/// 下载地址列表：全局设置了镜像时，镜像地址（镜像根目录 + path）排在内置下载源之前
+pub fn with_mirror(tool: &str, path: &str, mut urls: Vec<String>) -> Vec<String> {
+    if let Some(mirror) = settings::mirror(tool) {
+        settings::debug(format_args!("using {} mirror {}", tool, mirror));
+        urls.insert(0, format!("{}/{}", mirror, path.trim_start_matches('/')));
+    }
+    urls
+}
+
+/// 安装工具并在安装目录写入安装回执
+///
+/// 给出 pin 时只从锁定的地址下载，哈希不一致则安装失败。
+pub async fn install(tool: &str, version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<()> {
+    if let Some(artifact) = install_artifact(tool, version, install_dir, pin).await? {
+        artifact::write_receipt(install_dir, &artifact)?;
+    }
+    Ok(())
+}
+
+/// 安装工具，返回实际下载的安装包；已安装时返回 None
+pub async fn install_artifact(
+    tool: &str,
+    version: &str,
+    install_dir: &Path,
+    pin: Option<&Artifact>,
+) -> Result<Option<Artifact>> {
     match tool {
-        "node" => node::install(version, install_dir).await,
-        "java" | "jdk" => java::install(version, install_dir).await,
-        "mysql" => mysql::install(version, install_dir).await,
-        "mariadb" => mariadb::install(version, install_dir).await,  // 添加 MariaDB 支持
-        "python" => python::install(version, install_dir).await,  // 添加 Python 支持
-        "redis" => redis::install(version, install_dir).await,  // 添加 Redis 支持
+        "node" => node::install(version, install_dir, pin).await,
+        "java" | "jdk" => java::install(version, install_dir, pin).await,
+        "mysql" => mysql::install(version, install_dir, pin).await,
+        "mariadb" => mariadb::install(version, install_dir, pin).await,  // 添加 MariaDB 支持
+        "python" => python::install(version, install_dir, pin).await,  // 添加 Python 支持
+        "redis" => redis::install(version, install_dir, pin).await,  // 添加 Redis 支持
         _ => Err(anyhow::anyhow!(
             "Unsupported tool: '{}'. Supported: node, java, jdk, mysql, mariadb, python, redis",
             tool
@@ -24,8 +86,72 @@ pub async fn install(tool: &str, version: &str, install_dir: &Path) -> Result<()
     }
 }
 
-// 获取特定工具的可用版本列表
+/// 工具声明的可执行文件（不含扩展名）；未知工具只有与工具同名的可执行文件
+pub fn executables(tool: &str) -> &'static [&'static str] {
+    match tool {
+        "node" => node::EXECUTABLES,
+        "java" | "jdk" => java::EXECUTABLES,
+        "mysql" => mysql::EXECUTABLES,
+        "mariadb" => mariadb::EXECUTABLES,
+        "python" => python::EXECUTABLES,
+        "redis" => redis::EXECUTABLES,
+        _ => &[],
+    }
+}
+
+/// 服务的默认端口，`{{services.<name>.port}}` 在 .enmanrc 未指定端口时使用
+pub fn default_port(service: &str) -> Option<u16> {
+    match service {
+        "mysql" | "mariadb" => Some(3306),
+        "redis" => Some(6379),
+        _ => None,
+    }
+}
+
+/// 启动工具版本对应的服务（.enmanrc 中的 `[services]`），port 为 None 时使用默认端口；
+/// 返回是否启动了新的实例（服务已经在运行时为 false）
+pub fn start_service(tool: &str, install_dir: &Path, port: Option<u16>) -> Result<bool> {
+    match tool {
+        "mysql" | "mariadb" => mysql::start_mysql_service(install_dir, port),
+        "redis" => redis::start_redis_service(install_dir, port),
+        _ => Err(anyhow::anyhow!("'{}' is not a service. Supported services: {}", tool, SERVICES.join(", "))),
+    }
+}
+
+// 获取特定工具的可用版本列表，成功时顺便更新本地缓存
 pub async fn list_available_versions(tool: &str, limit: Option<usize>) -> Result<Vec<String>> {
+    let versions = fetch_available_versions(tool, limit).await?;
+    if let Ok(paths) = EnvManPaths::new() {
+        // 缓存只是补全用的辅助数据，写入失败不影响命令本身
+        let _ = cache_versions(&paths, tool, &versions);
+    }
+    Ok(versions)
+}
+
+/// 缓存中的远程版本（从未获取过时为空）
+pub fn cached_versions(paths: &EnvManPaths, tool: &str) -> Vec<String> {
+    std::fs::read_to_string(paths.remote_versions_cache(tool))
+        .map(|content| content.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// 把获取到的版本并入缓存；`enman list -r` 只获取最新的几个版本，因此合并而不是覆盖
+fn cache_versions(paths: &EnvManPaths, tool: &str, versions: &[String]) -> Result<()> {
+    let mut cached = cached_versions(paths, tool);
+    cached.extend(versions.iter().cloned());
+    cached.sort();
+    cached.dedup();
+    cached.sort_by(|a, b| crate::core::version::compare_versions(b, a));
+
+    let file = paths.remote_versions_cache(tool);
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(&file, cached.join("\n") + "\n")?;
+    Ok(())
+}
+
+async fn fetch_available_versions(tool: &str, limit: Option<usize>) -> Result<Vec<String>> {
     match tool {
         "node" => Ok(node::list_available_versions(limit).await?),
         "java" | "jdk" => Ok(java::list_available_versions(limit).await?),
@@ -1,41 +1,37 @@
 // src/downloader/mysql.rs
 use anyhow::{bail, Context, Result};
-use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::io::Read;
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::time::Duration;
 use std::process::Command;
-use std::io::{self, BufRead, BufReader};
-use std::net::TcpListener;
 use std::process::Stdio;
-use std::thread;
-use std::time::Duration;
-use tempfile::TempDir;
-use tokio::fs as async_fs;
-use tokio::io::AsyncWriteExt;
-use futures_util::StreamExt;
-use rpassword;
-use zip;
-
-use crate::core::paths;
-
-pub async fn download_mysql(version: &str, dest: &Path) -> Result<()> {
-    // 获取用户主目录
-    let home_dir = dirs::home_dir().context("Could not find home directory")?;
-    let enman_dir = home_dir.join(".enman");
-
-    // 创建下载缓存目录
-    let cache_dir = enman_dir.join("cache");
+
+use super::artifact::{self, Artifact};
+use crate::core::paths::EnvManPaths;
+use crate::core::settings;
+
+/// 配置文件中没有端口时 mysqld 使用的端口
+const DEFAULT_PORT: u16 = 3306;
+
+/// MySQL 发行包中的客户端和服务端程序，每个都会创建 shim
+pub const EXECUTABLES: &[&str] = &["mysql", "mysqld", "mysqladmin", "mysqldump", "mysqlimport", "mysqlshow", "mysqlcheck"];
+
+pub async fn download_mysql(version: &str, dest: &Path, pin: Option<&Artifact>) -> Result<Artifact> {
+    // 创建下载缓存目录（全局设置 cache_dir 可以修改位置）
+    let cache_dir = EnvManPaths::new()?.cache;
     fs::create_dir_all(&cache_dir)?;
 
     // 根据版本确定下载URL
     // 这里仅为示例，实际使用时需要根据版本选择对应的下载链接
-    let url = if version.starts_with("8.") {
-        format!("https://dev.mysql.com/get/Downloads/MySQL-{}/mysql-{}-winx64.zip", 
-                &version[..3], version)
+    let base = settings::mirror("mysql").unwrap_or("https://dev.mysql.com/get/Downloads");
+    let url = if let Some(pin) = pin {
+        pin.url.clone()
+    } else if version.starts_with("8.") {
+        format!("{}/MySQL-{}/mysql-{}-winx64.zip", base, &version[..3], version)
     } else {
-        format!("https://dev.mysql.com/get/Downloads/MySQL-{}/mysql-{}-winx64.msi", 
-                &version[..3], version)
+        format!("{}/MySQL-{}/mysql-{}-winx64.msi", base, &version[..3], version)
     };
 
     // 确定下载文件名
@@ -46,7 +42,7 @@ pub async fn download_mysql(version: &str, dest: &Path) -> Result<()> {
         println!("Downloading MySQL {}...", version);
 
         // 使用reqwest下载文件
-        let response = reqwest::get(&url).await
+        let response = super::http_client()?.get(&url).send().await
             .with_context(|| format!("Failed to download from: {}", url))?;
 
         if !response.status().is_success() {
@@ -65,6 +61,15 @@ pub async fn download_mysql(version: &str, dest: &Path) -> Result<()> {
         println!("Using cached MySQL {} archive", version);
     }
 
+    // 缓存的安装包同样需要校验，校验失败时删除缓存以便下次重新下载
+    let artifact = match artifact::verify(&url, &filename, pin) {
+        Ok(artifact) => artifact,
+        Err(e) => {
+            fs::remove_file(&filename).ok();
+            return Err(e);
+        }
+    };
+
     // 创建目标目录
     fs::create_dir_all(dest)?;
 
@@ -124,7 +129,7 @@ pub async fn download_mysql(version: &str, dest: &Path) -> Result<()> {
     std::fs::remove_dir_all(&extracted_dir)?;
 
     println!("MySQL {} installed successfully", version);
-    Ok(())
+    Ok(artifact)
 }
 
 pub fn setup_mysql_initial_config(install_path: &Path) -> Result<()> {
@@ -162,102 +167,6 @@ pub fn setup_mysql_initial_config(install_path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn configure_mysql_auto(install_path: &Path) -> Result<()> {
-    // 获取用户输入的密码
-    print!("Enter root password (min 8 chars): ");
-    std::io::stdout().flush()?;
-    let mut password = String::new();
-    std::io::stdin().read_line(&mut password)?;
-    password = password.trim().to_string();
-
-    if password.len() < 8 {
-        println!("Password is too short. Using default password 'root123'");
-        password = "root123".to_string();
-    }
-
-    // 创建一个临时的init文件用于更改密码
-    let init_sql_path = install_path.join("reset_password.sql");
-    let sql_content = format!(
-        "ALTER USER 'root'@'localhost' IDENTIFIED BY '{}';\nFLUSH PRIVILEGES;\n",
-        password
-    );
-    fs::write(&init_sql_path, sql_content)?;
-
-    // 使用--init-file参数启动MySQL，这样可以在启动后立即执行密码设置
-    let mysqld_bin = install_path.join("bin").join("mysqld.exe");
-    let data_dir = install_path.join("data");
-    let config_path = install_path.join("my.ini");
-
-    let mut child = Command::new(&mysqld_bin)
-        .arg("--defaults-file=".to_string() + &config_path.display().to_string())
-        .arg("--skip-grant-tables")
-        .arg("--init-file=".to_string() + &init_sql_path.display().to_string())
-        .spawn()?;
-
-    // 等待一段时间让MySQL完成密码设置
-    std::thread::sleep(std::time::Duration::from_secs(5));
-
-    // 检查进程是否仍在运行
-    if child.try_wait()?.is_none() {
-        // 发送终止信号
-        child.kill()?;
-        let _ = child.wait()?;
-    }
-
-    // 删除临时SQL文件
-    fs::remove_file(&init_sql_path)?;
-
-    // 验证密码是否设置成功
-    let mysql_bin = install_path.join("bin").join("mysql.exe");
-    let test_connection = Command::new(&mysql_bin)
-        .arg("-u")
-        .arg("root")
-        .arg("-p")
-        .arg(&password)
-        .arg("-e")
-        .arg("SELECT 1;")
-        .output()?;
-
-    if test_connection.status.success() {
-        println!("MySQL root password set successfully");
-    } else {
-        // 如果上面的方法失败，尝试备用方法
-        println!("Primary method failed, attempting alternate password reset...");
-
-        // 启动mysqld --skip-grant-tables
-        let mut child = Command::new(&mysqld_bin)
-            .arg("--defaults-file=".to_string() + &config_path.display().to_string())
-            .arg("--skip-grant-tables")
-            .spawn()?;
-
-        std::thread::sleep(std::time::Duration::from_secs(3));
-
-        // 使用mysql客户端执行密码更改
-        let result = Command::new(&mysql_bin)
-            .arg("-u")
-            .arg("root")
-            .arg("-e")
-            .arg(format!("ALTER USER 'root'@'localhost' IDENTIFIED BY '{}'; FLUSH PRIVILEGES;", password))
-            .output()?;
-
-        // 结束mysqld进程
-        if child.try_wait()?.is_none() {
-            child.kill()?;
-            let _ = child.wait()?;
-        }
-
-        if result.status.success() {
-            println!("MySQL root password set successfully with alternate method");
-        } else {
-            let stderr = String::from_utf8_lossy(&result.stderr);
-            bail!("Failed to set MySQL password: {}", stderr);
-        }
-    }
-
-    Ok(())
-}
-
-
 pub fn stop_current_mysql_service() -> Result<()> {
     // 首先尝试使用wmic命令查找mysqld进程
     let output = Command::new("wmic")
@@ -293,45 +202,17 @@ pub fn stop_current_mysql_service() -> Result<()> {
 }
 
 // 添加 install 函数
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
-    download_mysql(version, install_dir).await?;
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
+    let artifact = download_mysql(version, install_dir, pin).await?;
     setup_mysql_initial_config(install_dir)?;
-    Ok(())
-}
-
-// 创建初始配置文件的函数
-fn create_init_config(install_dir: &Path, data_dir: &Path) -> Result<PathBuf> {
-    let config_path = install_dir.join("temp_my.ini");
-    let config_content = format!(
-        "[mysqld]\nskip-networking\nport=3306\ndatadir={}\nbasedir={}\n",
-        data_dir.display(),
-        install_dir.display()
-    );
-    std::fs::write(&config_path, config_content)?;
-    Ok(config_path)
-}
-
-// 创建默认配置文件的函数
-fn create_default_config(install_dir: &Path, data_dir: &Path, port: u16) -> Result<()> {
-    let config_path = if cfg!(windows) {
-        install_dir.join("my.ini")
-    } else {
-        install_dir.join("my.cnf")
-    };
-    
-    let config_content = format!(
-        "[mysqld]\nport={}\ndatadir={}\nbasedir={}\n\n[mysql]\ndefault-character-set=utf8mb4\n",
-        port,
-        data_dir.display(),
-        install_dir.display()
-    );
-    
-    std::fs::write(&config_path, config_content)?;
-    Ok(())
+    Ok(Some(artifact))
 }
 
-/// 启动MySQL服务的函数
-pub fn start_mysql_service(install_dir: &Path) -> Result<()> {
+/// 启动MySQL服务的函数（MariaDB 的目录结构相同，也使用这个函数）
+///
+/// port 为 None 时使用配置文件中的端口。返回是否启动了新的实例：端口上已经有 MySQL 在运行时
+/// 不再启动第二个 mysqld（两个实例会争用同一个数据目录）。
+pub fn start_mysql_service(install_dir: &Path, port: Option<u16>) -> Result<bool> {
     let mysqld_bin = if cfg!(windows) {
         install_dir.join("bin").join("mysqld.exe")
     } else {
@@ -348,8 +229,21 @@ pub fn start_mysql_service(install_dir: &Path) -> Result<()> {
         bail!("Configuration file does not exist: {}", config_path.display());
     }
 
+    let listen_port = port
+        .or_else(|| fs::read_to_string(&config_path).ok().and_then(|content| configured_port(&content)))
+        .unwrap_or(DEFAULT_PORT);
+    match greeting(listen_port) {
+        Some(true) => {
+            eprintln!("✅ MySQL is already running on port {}", listen_port);
+            return Ok(false);
+        }
+        Some(false) => bail!("port {} is already in use by another program", listen_port),
+        None => {}
+    }
+
     eprintln!("🚀 Starting MySQL service...");
-    
+    let port_args: Vec<String> = port.map(|port| format!("--port={}", port)).into_iter().collect();
+
     #[cfg(windows)]
     {
         use std::os::windows::process::CommandExt;
@@ -359,6 +253,7 @@ pub fn start_mysql_service(install_dir: &Path) -> Result<()> {
         let _ = Command::new(&mysqld_bin)
             .creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS)
             .arg(format!("--defaults-file={}", config_path.display()))
+            .args(&port_args)
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .spawn()?;
@@ -368,6 +263,7 @@ pub fn start_mysql_service(install_dir: &Path) -> Result<()> {
     {
         let _ = Command::new(&mysqld_bin)
             .arg(format!("--defaults-file={}", config_path.display()))
+            .args(&port_args)
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .spawn()?;
@@ -375,91 +271,64 @@ pub fn start_mysql_service(install_dir: &Path) -> Result<()> {
 
     std::thread::sleep(std::time::Duration::from_millis(1500));
     eprintln!("✅ MySQL service started successfully!");
-    Ok(())
+    Ok(true)
 }
 
-
-/// 使用 --init-file 设置密码（可靠方案）
-async fn configure_and_start_mysql(install_dir: &Path, port: u16, root_password: String) -> Result<()> {
-    let data_dir = install_dir.join("data");
-
-    // Step 1: 写 init.sql
-    let init_sql = install_dir.join("init.sql");
-    fs::write(&init_sql, format!(
-        "ALTER USER 'root'@'localhost' IDENTIFIED BY '{}';\nFLUSH PRIVILEGES;",
-        root_password
-    ))?;
-
-    // Step 2: 创建无网络临时配置
-    let temp_config = create_init_config(install_dir, &data_dir)?;
-
-    let mysqld_bin = if cfg!(windows) {
-        install_dir.join("bin").join("mysqld.exe")
-    } else {
-        install_dir.join("bin").join("mysqld")
-    };
-
-    eprintln!("🔐 Setting root password via --init-file (no network)...");
-
-    // 启动一次，应用密码
-    let mut child = Command::new(&mysqld_bin)
-        .arg(format!("--defaults-file={}", temp_config.display()))
-        .arg(format!("--init-file={}", init_sql.display()))
-        .arg("--console")
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .spawn()?;
-
-    std::thread::sleep(std::time::Duration::from_secs(3));
-    let _ = child.kill();
-    let _ = child.wait();
-
-    // 清理
-    let _ = fs::remove_file(&init_sql);
-    let _ = fs::remove_file(&temp_config);
-
-    eprintln!("✅ Root password set successfully!");
-
-    // Step 3: 写正式配置（带端口）
-    create_default_config(install_dir, &data_dir, port)?;
-
-    // Step 4: 启动正式服务
-    let config_path = if cfg!(windows) {
-        install_dir.join("my.ini")
-    } else {
-        install_dir.join("my.cnf")
-    };
-
-    eprintln!("🚀 Starting MySQL service on port {}...", port);
-
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
-        const DETACHED_PROCESS: u32 = 0x00000008;
-
-        let _ = Command::new(&mysqld_bin)
-            .creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS)
-            .arg(format!("--defaults-file={}", config_path.display()))
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn();
-    }
-
-    #[cfg(not(windows))]
-    {
-        let _ = Command::new(&mysqld_bin)
-            .arg(format!("--defaults-file={}", config_path.display()))
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn();
+/// 配置文件 `[mysqld]` 中的端口
+fn configured_port(config: &str) -> Option<u16> {
+    let mut in_mysqld = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_mysqld = line == "[mysqld]";
+        } else if let Some((key, value)) = line.split_once('=') {
+            if in_mysqld && key.trim() == "port" {
+                return value.trim().parse().ok();
+            }
+        }
     }
+    None
+}
 
-    std::thread::sleep(std::time::Duration::from_millis(1500));
-    eprintln!("✅ MySQL is running in the background on port {}!", port);
-    Ok(())
+/// 端口上是否有 MySQL 在运行：没有程序监听时为 None，有程序监听但不是 MySQL 时为 Some(false)
+///
+/// MySQL 在连接建立后先发送握手包（协议版本 10），拒绝连接时发送错误包（0xff）。
+fn greeting(port: u16) -> Option<bool> {
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&address, Duration::from_millis(300)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_millis(500))).ok();
+    let mut packet = [0u8; 64];
+    let read = stream.read(&mut packet).unwrap_or(0);
+    // 前 4 个字节是包头（长度和序号）
+    Some(read > 4 && matches!(packet[4], 0x0a | 0xff))
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// 在随机端口上监听，连接建立后发送 greeting，返回端口
+    fn serve(greeting: &'static [u8]) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let _ = stream.write_all(greeting);
+            }
+        });
+        port
+    }
 
+    #[test]
+    fn test_greeting_detects_existing_instances() {
+        assert_eq!(greeting(serve(b"\x4a\x00\x00\x00\x0a8.0.36\x00")), Some(true));
+        assert_eq!(greeting(serve(b"HTTP/1.1 400 Bad Request\r\n")), Some(false));
 
+        let unused = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        assert_eq!(greeting(unused), None);
 
+        assert_eq!(configured_port("[mysql]\nport=1\n\n[mysqld]\nport = 3307\n"), Some(3307));
+        assert_eq!(configured_port("[client]\nport=3308\n"), None);
+    }
+}
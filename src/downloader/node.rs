@@ -4,8 +4,13 @@ use indicatif::{ProgressBar, ProgressStyle};
 use std::path::Path;
 use std::fs;
 use tempfile::TempDir;
+
+use super::artifact::{self, Artifact};
 use tokio::io::AsyncWriteExt;
 
+/// Node.js 发行包自带的可执行文件，每个都会创建 shim
+pub const EXECUTABLES: &[&str] = &["node", "npm", "npx", "corepack"];
+
 // 注意：这些函数现在只服务于 node，所以可以简化
 
 fn detect_platform() -> Result<(&'static str, &'static str, &'static str)> {
@@ -42,14 +47,18 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
     let (os, arch, ext) = detect_platform()?;
     let filename = format!("node-v{}-{}-{}.{}", version, os, arch, ext);
     
-    Ok(vec![
-        format!("https://nodejs.org/dist/v{}/{}", version, filename),
-        format!("https://npmmirror.com/mirrors/node/v{}/{}", version, filename),
-    ])
+    Ok(super::with_mirror(
+        "node",
+        &format!("v{}/{}", version, filename),
+        vec![
+            format!("https://nodejs.org/dist/v{}/{}", version, filename),
+            format!("https://npmmirror.com/mirrors/node/v{}/{}", version, filename),
+        ],
+    ))
 }
 
 async fn download_with_progress(url: &str, dest: &Path) -> Result<()> {
-    let res = reqwest::get(url).await.context("Failed to start download")?;
+    let res = super::http_client()?.get(url).send().await.context("Failed to start download")?;
     if !res.status().is_success() {
         return Err(anyhow::anyhow!("HTTP {}: {}", res.status(), url));
     }
@@ -138,7 +147,7 @@ fn extract_and_flatten(archive: &Path, dest: &Path, format: &str) -> Result<()>
     Ok(())
 }
 
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
     let node_bin = if cfg!(windows) {
         install_dir.join("node.exe")
     } else {
@@ -147,31 +156,32 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
 
     if node_bin.exists() {
         println!("⚠️  Node.js {} already installed", version);
-        return Ok(());
+        return Ok(None);
     }
 
     let (_os, _arch, ext) = detect_platform()?;
-    let urls = get_download_urls(version)?;
+    let urls = artifact::download_urls(pin, get_download_urls(version)?);
     let temp_dir = TempDir::new()?;
     let archive_path = temp_dir.path().join(format!("node.{}", ext));
 
-    let mut success = false;
+    let mut used_url = None;
     for (i, url) in urls.iter().enumerate() {
-        let source = if i == 0 { "Official" } else { "Mirror (npmmirror)" };
+        let source = if pin.is_some() { "Locked" } else if i == 0 { "Official" } else { "Mirror (npmmirror)" };
         eprintln!("📥 [{}] Trying: {}", source, url);
         
         if download_with_progress(url, &archive_path).await.is_ok() {
             eprintln!("✅ Using source: {}", source);
-            success = true;
+            used_url = Some(url);
             break;
         } else {
             eprintln!("⚠️  [{}] Failed", source);
         }
     }
 
-    if !success {
+    let Some(used_url) = used_url else {
         return Err(anyhow::anyhow!("All download sources failed"));
-    }
+    };
+    let artifact = artifact::verify(used_url, &archive_path, pin)?;
 
     extract_and_flatten(&archive_path, install_dir, ext)
         .context("Failed to extract Node.js")?;
@@ -181,14 +191,14 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
     }
 
     eprintln!("✨ Node.js {} installed to {}", version, install_dir.display());
-    Ok(())
+    Ok(Some(artifact))
 }
 
 // 获取 Node.js 可用版本的函数
 pub async fn list_available_versions(limit: Option<usize>) -> Result<Vec<String>> {
     // 从 Node.js API 获取版本列表
     let url = "https://nodejs.org/dist/index.json";
-    let response = reqwest::get(url).await?;
+    let response = super::http_client()?.get(url).send().await?;
     
     if !response.status().is_success() {
         return Err(anyhow::anyhow!("Failed to fetch Node.js versions list"));
@@ -5,6 +5,11 @@ use std::fs;
 use tempfile::TempDir;
 use tokio::io::AsyncWriteExt;
 
+use super::artifact::{self, Artifact};
+
+/// Python 发行包自带的可执行文件，每个都会创建 shim
+pub const EXECUTABLES: &[&str] = &["python", "python3", "pip", "pip3", "pythonw", "idle3", "pydoc3"];
+
 fn detect_platform() -> Result<(&'static str, &'static str, &'static str)> {
     let os = if cfg!(windows) {
         "win"
@@ -35,8 +40,9 @@ fn detect_platform() -> Result<(&'static str, &'static str, &'static str)> {
 
 fn get_download_urls(version: &str) -> Result<Vec<String>> {
     let (os, arch, _ext) = detect_platform()?;
-    
-    let urls = match os {
+    // 镜像使用与默认下载源相同的目录结构：Windows 为 python.org/ftp/python，
+    // 其他系统为 python-build-standalone 的 GitHub releases/download
+    let (mirror_path, urls) = match os {
         "win" => {
             // 对于 Windows，使用嵌入式 Python 版本，这是一个便携版本，无需安装
             let filename = match arch {
@@ -45,11 +51,12 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
                 _ => format!("python-{}-embed-amd64.zip", version), // 默认回退
             };
             
-            vec![
+            let mirror_path = format!("{}/{}", version, filename);
+            (mirror_path, vec![
                 format!("https://www.python.org/ftp/python/{}/{}", version, filename),
                 // 提供备用镜像
                 format!("https://npm.taobao.org/mirrors/python/{}/{}", version, filename),
-            ]
+            ])
         },
         "macos" => {
             // 对于 macOS，使用 python-build-standalone 提供的便携版本
@@ -59,10 +66,11 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
                 _ => "x86_64-apple-darwin-install_only.tar.gz", // 默认回退
             };
             
-            vec![
+            let mirror_path = format!("20231002/cpython-{}+20231002-{}", version, suffix);
+            (mirror_path, vec![
                 format!("https://github.com/indygreg/python-build-standalone/releases/download/20231002/cpython-{}+20231002-{}", 
                         version, suffix),
-            ]
+            ])
         },
         "linux" => {
             // 对于 Linux，同样使用 python-build-standalone 提供的便携版本
@@ -72,19 +80,20 @@ fn get_download_urls(version: &str) -> Result<Vec<String>> {
                 _ => "x86_64-unknown-linux-gnu-install_only.tar.gz", // 默认回退
             };
             
-            vec![
+            let mirror_path = format!("20231002/cpython-{}+20231002-{}", version, suffix);
+            (mirror_path, vec![
                 format!("https://github.com/indygreg/python-build-standalone/releases/download/20231002/cpython-{}+20231002-{}", 
                         version, suffix),
-            ]
+            ])
         },
         _ => return Err(anyhow::anyhow!("Unsupported OS")),
     };
     
-    Ok(urls)
+    Ok(super::with_mirror("python", &mirror_path, urls))
 }
 
 async fn download_with_progress(url: &str, dest: &Path) -> Result<()> {
-    let client = reqwest::ClientBuilder::new()
+    let client = super::client_builder()
         .user_agent("Mozilla/5.0 (compatible; enman)")  // 设置浏览器兼容的 User-Agent
         .build()?;
 
@@ -218,7 +227,7 @@ fn extract_and_install_python(archive: &Path, dest: &Path, ext: &str) -> Result<
     Ok(())
 }
 
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
     let python_bin = if cfg!(windows) {
         install_dir.join("python.exe")
     } else {
@@ -227,17 +236,19 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
 
     if python_bin.exists() {
         println!("⚠️  Python {} already installed", version);
-        return Ok(());
+        return Ok(None);
     }
 
     let (_os, _arch, ext) = detect_platform()?;
-    let urls = get_download_urls(version)?;
+    let urls = artifact::download_urls(pin, get_download_urls(version)?);
     let temp_dir = TempDir::new()?;
     let archive_path = temp_dir.path().join(format!("python.{}", ext));
 
-    let mut success = false;
+    let mut used_url = None;
     for (i, url) in urls.iter().enumerate() {
-        let source = if i == 0 { 
+        let source = if pin.is_some() {
+            "Locked"
+        } else if i == 0 { 
             if cfg!(windows) { "Official Python Embedded" } else { "Python Build Standalone" } 
         } else { 
             "Mirror" 
@@ -246,17 +257,17 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
         
         if download_with_progress(url, &archive_path).await.is_ok() {
             eprintln!("✅ Using source: {}", source);
-            success = true;
+            used_url = Some(url);
             break;
         } else {
             eprintln!("⚠️  [{}] Failed", source);
         }
     }
 
-    if !success {
+    let Some(used_url) = used_url else {
         // 提供手动安装说明
         eprintln!("❌ Unable to automatically download Python.");
-        eprintln!("");
+        eprintln!();
         eprintln!("💡 Manual installation steps:");
         eprintln!("   1. Visit: https://www.python.org/downloads/");
         eprintln!("   2. For Windows: Download 'Embeddable zip file' for your version");
@@ -264,9 +275,10 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
         eprintln!("   4. Extract to: {}", install_dir.display());
         eprintln!("   5. Run: enman global python@{}", version);
         return Err(anyhow::anyhow!("Automatic download failed."));
-    }
+    };
+    let artifact = artifact::verify(used_url, &archive_path, pin)?;
 
-    extract_and_install_python(&archive_path, install_dir, &ext)
+    extract_and_install_python(&archive_path, install_dir, ext)
         .context("Failed to extract and install Python")?;
 
     if !python_bin.exists() {
@@ -297,14 +309,14 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
 
     println!("✨ Python {} installed to {}", version, install_dir.display());
 
-    Ok(())
+    Ok(Some(artifact))
 }
 
 // 获取 Python 可用版本的函数
 pub async fn list_available_versions(limit: Option<usize>) -> Result<Vec<String>> {
     // 从 PyPI API 获取最新版本
     let url = "https://pypi.org/pypi/python/json";
-    let response = reqwest::get(url).await?;
+    let response = super::http_client()?.get(url).send().await?;
     
     if !response.status().is_success() {
         return Err(anyhow::anyhow!("Failed to fetch Python versions list"));
@@ -4,33 +4,37 @@ use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::Deserialize;
 use std::fs;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
 use std::path::Path;
-use tokio;
 use std::path::PathBuf;
+use std::time::Duration;
 use zip::ZipArchive;
 use flate2::read::GzDecoder;
 use tar::Archive;
 
+use super::artifact::{self, Artifact};
+
+const DEFAULT_PORT: u16 = 6379;
+
+/// Redis 发行包中的可执行文件，每个都会创建 shim
+pub const EXECUTABLES: &[&str] = &[
+    "redis-server", "redis-cli", "redis-benchmark", "redis-check-aof", "redis-check-rdb", "redis-sentinel",
+];
+
 #[derive(Debug, Deserialize)]
 struct RedisRelease {
     tag_name: String,
-    assets: Vec<RedisAsset>,
-}
-
-#[derive(Debug, Deserialize)]
-struct RedisAsset {
-    name: String,
-    browser_download_url: String,
 }
 
-pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
+pub async fn install(version: &str, install_dir: &Path, pin: Option<&Artifact>) -> Result<Option<Artifact>> {
     println!("🔍 Installing redis @ {}", version);
 
     // 检查是否已安装
     if install_dir.exists() {
         println!("✅ redis @ {} already installed", version);
         println!("💡 Tip: to reinstall please uninstall first");
-        return Ok(());
+        return Ok(None);
     }
 
     // 确保安装目录存在
@@ -40,35 +44,107 @@ pub async fn install(version: &str, install_dir: &Path) -> Result<()> {
     let temp_dir = std::env::temp_dir().join(format!("enman_redis_temp_{}", version));
     fs::create_dir_all(&temp_dir)?;
     
-    let archive_path = download_redis(&temp_dir, version).await?;
+    let (url, archive_path) = download_redis(&temp_dir, version, pin.map(|pin| pin.url.as_str())).await?;
+    let artifact = match artifact::verify(&url, &archive_path, pin) {
+        Ok(artifact) => artifact,
+        Err(e) => {
+            fs::remove_dir_all(&temp_dir).ok();
+            fs::remove_dir_all(install_dir).ok();
+            return Err(e);
+        }
+    };
     extract_archive(&archive_path, install_dir)?;
     
     // 清理临时目录
     fs::remove_dir_all(&temp_dir)?;
 
     println!("🎉 Successfully installed redis @ {}", version);
-    Ok(())
+    Ok(Some(artifact))
 }
 
-pub async fn install_redis_version(version: &str) -> Result<()> {
-    let paths = EnvManPaths::new()?;
-    let install_dir = paths.install_dir("redis").join(version);
-    install(version, &install_dir).await
+/// 在后台启动 redis-server，port 为 None 时使用默认端口 6379；返回是否启动了新的实例
+///
+/// 端口上已经有 Redis 在运行时不再启动，重复执行 `config apply` 不会产生多个实例。
+/// 端口被其他程序占用，或者 redis-server 没能开始响应时返回错误，输出在安装目录的 redis-server.log 中。
+pub fn start_redis_service(install_dir: &Path, port: Option<u16>) -> Result<bool> {
+    let exe = if cfg!(windows) { "redis-server.exe" } else { "redis-server" };
+    let server = [install_dir.join("bin").join(exe), install_dir.join(exe)]
+        .into_iter()
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow!("redis-server not found in {}", install_dir.display()))?;
+
+    let listen_port = port.unwrap_or(DEFAULT_PORT);
+    match ping(listen_port) {
+        Some(true) => {
+            eprintln!("✅ Redis is already running on port {}", listen_port);
+            return Ok(false);
+        }
+        Some(false) => return Err(anyhow!("port {} is already in use by another program", listen_port)),
+        None => {}
+    }
+
+    let log_path = install_dir.join("redis-server.log");
+    let log = fs::File::create(&log_path)?;
+    let mut command = std::process::Command::new(&server);
+    if let Some(port) = port {
+        command.arg("--port").arg(port.to_string());
+    }
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        const DETACHED_PROCESS: u32 = 0x00000008;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP | DETACHED_PROCESS);
+    }
+
+    let mut child = command.spawn()?;
+    // 等待 Redis 开始响应；在此之前进程退出说明启动失败
+    for _ in 0..50 {
+        if let Some(status) = child.try_wait()? {
+            return Err(anyhow!("redis-server exited with {}; see {}", status, log_path.display()));
+        }
+        if ping(listen_port) == Some(true) {
+            eprintln!("✅ Redis service started on port {}", listen_port);
+            return Ok(true);
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    child.kill().ok();
+    Err(anyhow!("redis-server did not respond on port {} within 5 seconds; see {}", listen_port, log_path.display()))
 }
 
-async fn download_redis(temp_dir: &Path, version: &str) -> Result<PathBuf> {
+/// 向本机的端口发送 PING：None 表示没有程序在监听，Some(true) 表示是 Redis
+fn ping(port: u16) -> Option<bool> {
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&address, Duration::from_millis(300)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_millis(500))).ok();
+    let mut reply = [0u8; 64];
+    let read = stream.write_all(b"PING\r\n").and_then(|_| stream.read(&mut reply)).unwrap_or(0);
+    // 设置了密码的 Redis 回复 -NOAUTH
+    Some(reply[..read].starts_with(b"+PONG") || reply[..read].starts_with(b"-NOAUTH"))
+}
+
+/// 下载 Redis 安装包，返回 (实际使用的下载地址, 安装包路径)；pinned_url 为锁文件中记录的地址
+async fn download_redis(temp_dir: &Path, version: &str, pinned_url: Option<&str>) -> Result<(String, PathBuf)> {
     let client = create_http_client();
 
     // 构建下载URL
-    let download_url = if cfg!(windows) {
+    let download_url = if let Some(url) = pinned_url {
+        url.to_string()
+    } else if cfg!(windows) {
         // Windows上的Redis通常使用TPoradowski的分发
         // 由于官方不提供Windows版本，我们使用GitHub上的第三方构建
         // 首先尝试最可能存在的URL格式
-        let urls_to_try = [
+        let urls_to_try = super::with_mirror("redis", &format!("v{}/Redis-{}-x64.zip", version, version), vec![
             format!("https://github.com/tporadowski/redis/releases/download/v{}/Redis-{}-x64.zip", version, version),
             format!("https://github.com/tporadowski/redis/releases/download/{}/Redis-{}-x64.zip", version, version),
             format!("https://github.com/redis-windows/redis/releases/download/{}/redis-{}.zip", version, version),
-        ];
+        ]);
 
         let mut last_error = None;
         for url in &urls_to_try {
@@ -83,7 +159,7 @@ async fn download_redis(temp_dir: &Path, version: &str) -> Result<PathBuf> {
                         fs::write(&archive_path, bytes)?;
 
                         println!("✅ Download completed from: {}", url);
-                        return Ok(archive_path);
+                        return Ok((url.clone(), archive_path));
                     } else {
                         last_error = Some(anyhow!("HTTP Error: {}", response.status()));
                         println!("⚠️ Download failed from: {} - HTTP Error: {}", url, response.status());
@@ -104,8 +180,9 @@ async fn download_redis(temp_dir: &Path, version: &str) -> Result<PathBuf> {
             None => anyhow!("No Windows Redis download URLs attempted"),
         });
     } else {
-        // Linux/Mac版本 - 从Redis官网获取
-        format!("https://download.redis.io/releases/redis-{}.tar.gz", version)
+        // Linux/Mac版本 - 从Redis官网获取，设置了镜像时使用与 download.redis.io/releases 相同的目录结构
+        let base = crate::core::settings::mirror("redis").unwrap_or("https://download.redis.io/releases");
+        format!("{}/redis-{}.tar.gz", base, version)
     };
 
     println!("📥 Downloading redis @ {}...", version);
@@ -124,7 +201,7 @@ async fn download_redis(temp_dir: &Path, version: &str) -> Result<PathBuf> {
         ));
     }
 
-    let file_extension = if cfg!(windows) { ".zip" } else { ".tar.gz" };
+    let file_extension = if download_url.ends_with(".zip") { ".zip" } else { ".tar.gz" };
     let archive_path = temp_dir.join(format!("redis-{}{}", version, file_extension));
 
     let bytes = response.bytes().await?;
@@ -132,7 +209,7 @@ async fn download_redis(temp_dir: &Path, version: &str) -> Result<PathBuf> {
 
     println!("✅ Download completed");
 
-    Ok(archive_path)
+    Ok((download_url, archive_path))
 }
 
 fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<()> {
@@ -156,7 +233,8 @@ fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<()> {
 
 fn create_http_client() -> ClientWithMiddleware {
     let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-    ClientBuilder::new(reqwest::Client::new())
+    let client = super::http_client().unwrap_or_default();
+    ClientBuilder::new(client)
         .with(RetryTransientMiddleware::new_with_policy(retry_policy))
         .build()
 }
@@ -198,21 +276,6 @@ pub async fn list_redis_versions() -> Result<Vec<String>> {
     }
 }
 
-pub fn is_redis_installed(version: &str) -> bool {
-    let paths = match EnvManPaths::new() {
-        Ok(paths) => paths,
-        Err(_) => return false,
-    };
-
-    let install_dir = paths.install_dir("redis").join(version);
-    install_dir.exists()
-}
-
-pub fn get_redis_install_path(version: &str) -> Result<std::path::PathBuf> {
-    let paths = EnvManPaths::new()?;
-    Ok(paths.install_bin_path("redis", version))
-}
-
 pub fn uninstall_redis_version(version: &str) -> Result<()> {
     let paths = EnvManPaths::new()?;
     let install_dir = paths.install_dir("redis").join(version);
@@ -253,4 +316,33 @@ fn is_redis_dir_empty(redis_dir: &Path) -> Result<bool> {
 
     let entries: Result<Vec<_>, _> = fs::read_dir(redis_dir)?.collect();
     Ok(entries?.is_empty())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// 在随机端口上监听，对第一个连接回复 reply，返回端口
+    fn serve(reply: &'static [u8]) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut request = [0u8; 16];
+                let _ = stream.read(&mut request);
+                let _ = stream.write_all(reply);
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn test_ping_detects_existing_instances() {
+        assert_eq!(ping(serve(b"+PONG\r\n")), Some(true));
+        assert_eq!(ping(serve(b"HTTP/1.1 400 Bad Request\r\n")), Some(false));
+
+        let unused = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        assert_eq!(ping(unused), None);
+    }
+}
@@ -18,14 +18,25 @@ impl Localizer {
         en_translations.insert("command_install_description".to_string(), "Install a specific version of a tool".to_string());
         en_translations.insert("command_global_description".to_string(), "Set global default tool version".to_string());
         en_translations.insert("command_list_description".to_string(), "List installed or available versions".to_string());
-        en_translations.insert("command_use_description".to_string(), "Temporarily switch tool version for current session".to_string());
+        en_translations.insert("command_use_description".to_string(), "Set the tool version for the current directory".to_string());
         en_translations.insert("command_uninstall_description".to_string(), "Uninstall a specific version of a tool".to_string());
         en_translations.insert("command_config_description".to_string(), "Manage project-level configuration".to_string());
+        en_translations.insert("command_current_description".to_string(), "Show the active version of each tool and where it was set".to_string());
+        en_translations.insert("command_which_description".to_string(), "Print the absolute path of a tool's active binary".to_string());
+        en_translations.insert("command_why_description".to_string(), "Explain which file chose a tool's version".to_string());
+        en_translations.insert("command_lock_description".to_string(), "Write .enman.lock with exact versions and checksums".to_string());
+        en_translations.insert("command_reshim_description".to_string(), "Rebuild shims for all installed tools".to_string());
+        en_translations.insert("command_activate_description".to_string(), "Print the shell integration hook".to_string());
+        en_translations.insert("command_exec_description".to_string(), "Run a command with specific tool versions".to_string());
+        en_translations.insert("command_completions_description".to_string(), "Generate shell completions".to_string());
+        en_translations.insert("command_run_description".to_string(), "Run project tasks from .enmanrc".to_string());
+        en_translations.insert("command_shell_description".to_string(), "Switch tool version for the current shell session".to_string());
         en_translations.insert("arg_tool_version_help".to_string(), "Tool and version in format: tool@version (e.g., node@20.10.0)".to_string());
         en_translations.insert("arg_tool_help".to_string(), "Tool name (e.g., node, python, java)".to_string());
         en_translations.insert("arg_version_help".to_string(), "Version (e.g., 18.17.0, latest)".to_string());
         en_translations.insert("arg_remote_help".to_string(), "Show remote available versions".to_string());
         en_translations.insert("arg_available_help".to_string(), "Show all available tools".to_string());
+        en_translations.insert("arg_profile_help".to_string(), "Use a profile from .enmanrc [profiles.<name>] (same as ENMAN_PROFILE)".to_string());
         en_translations.insert("Installing".to_string(), "Installing".to_string());
         en_translations.insert("Switching to".to_string(), "Switching to".to_string());
         en_translations.insert("Warning: Could not stop current MySQL service:".to_string(), "Warning: Could not stop current MySQL service:".to_string());
@@ -51,6 +62,9 @@ impl Localizer {
         en_translations.insert("global".to_string(), "global".to_string());
         en_translations.insert("not installed".to_string(), "not installed".to_string());
         en_translations.insert("No global versions set.".to_string(), "No global versions set.".to_string());
+        en_translations.insert("No versions set.".to_string(), "No versions set.".to_string());
+        en_translations.insert("No version set".to_string(), "No version set".to_string());
+        en_translations.insert("not found on PATH".to_string(), "not found on PATH".to_string());
         
         // 中文翻译
         let mut zh_translations = HashMap::new();
@@ -59,14 +73,25 @@ impl Localizer {
         zh_translations.insert("command_install_description".to_string(), "安装指定版本的工具".to_string());
         zh_translations.insert("command_global_description".to_string(), "设置全局默认工具版本".to_string());
         zh_translations.insert("command_list_description".to_string(), "列出已安装或可用的版本".to_string());
-        zh_translations.insert("command_use_description".to_string(), "临时切换当前会话的工具版本".to_string());
+        zh_translations.insert("command_use_description".to_string(), "设置当前目录使用的工具版本".to_string());
         zh_translations.insert("command_uninstall_description".to_string(), "卸载指定版本的工具".to_string());
         zh_translations.insert("command_config_description".to_string(), "管理项目级配置".to_string());
+        zh_translations.insert("command_current_description".to_string(), "显示每个工具当前生效的版本及其来源".to_string());
+        zh_translations.insert("command_which_description".to_string(), "显示工具当前生效的可执行文件的绝对路径".to_string());
+        zh_translations.insert("command_why_description".to_string(), "说明工具版本由哪个文件决定".to_string());
+        zh_translations.insert("command_lock_description".to_string(), "生成 .enman.lock，记录精确版本和校验和".to_string());
+        zh_translations.insert("command_reshim_description".to_string(), "为所有已安装的工具重建 shim".to_string());
+        zh_translations.insert("command_activate_description".to_string(), "输出 shell 集成脚本".to_string());
+        zh_translations.insert("command_exec_description".to_string(), "使用指定的工具版本运行命令".to_string());
+        zh_translations.insert("command_completions_description".to_string(), "生成 shell 补全脚本".to_string());
+        zh_translations.insert("command_run_description".to_string(), "运行 .enmanrc 中定义的项目任务".to_string());
+        zh_translations.insert("command_shell_description".to_string(), "临时切换当前 shell 会话的工具版本".to_string());
         zh_translations.insert("arg_tool_version_help".to_string(), "工具和版本，格式：tool@version (例如，node@20.10.0)".to_string());
         zh_translations.insert("arg_tool_help".to_string(), "工具名称 (例如，node, python, java)".to_string());
         zh_translations.insert("arg_version_help".to_string(), "版本 (例如，18.17.0, latest)".to_string());
         zh_translations.insert("arg_remote_help".to_string(), "显示远程可用版本".to_string());
         zh_translations.insert("arg_available_help".to_string(), "显示所有可用工具".to_string());
+        zh_translations.insert("arg_profile_help".to_string(), "使用 .enmanrc 中的配置方案 [profiles.<name>]（等同于 ENMAN_PROFILE）".to_string());
         zh_translations.insert("Installing".to_string(), "正在安装".to_string());
         zh_translations.insert("Switching to".to_string(), "正在切换到".to_string());
         zh_translations.insert("Warning: Could not stop current MySQL service:".to_string(), "警告：无法停止当前 MySQL 服务：".to_string());
@@ -92,6 +117,9 @@ impl Localizer {
         zh_translations.insert("global".to_string(), "全局".to_string());
         zh_translations.insert("not installed".to_string(), "未安装".to_string());
         zh_translations.insert("No global versions set.".to_string(), "未设置全局版本。".to_string());
+        zh_translations.insert("No versions set.".to_string(), "未设置任何版本。".to_string());
+        zh_translations.insert("No version set".to_string(), "未设置版本".to_string());
+        zh_translations.insert("not found on PATH".to_string(), "PATH 中未找到".to_string());
         
         translations.insert("en".to_string(), en_translations);
         translations.insert("zh".to_string(), zh_translations);
@@ -125,6 +153,11 @@ impl Localizer {
 }
 
 fn detect_language() -> String {
+    // 全局设置中指定的语言优先
+    if let Some(lang) = &crate::core::settings::global().language {
+        return lang.clone();
+    }
+
     // 尝试从环境变量检测语言
     if let Ok(lang) = std::env::var("LANG") {
         if lang.starts_with("zh") {
@@ -167,16 +200,10 @@ impl Default for Localizer {
 }
 
 // 全局本地化实例
-static mut LOCALIZER: Option<Localizer> = None;
-static INIT: std::sync::Once = std::sync::Once::new();
+static LOCALIZER: std::sync::OnceLock<Localizer> = std::sync::OnceLock::new();
 
 pub fn get_localizer() -> &'static Localizer {
-    unsafe {
-        INIT.call_once(|| {
-            LOCALIZER = Some(Localizer::new());
-        });
-        LOCALIZER.as_ref().unwrap()
-    }
+    LOCALIZER.get_or_init(Localizer::new)
 }
 
 #[macro_export]
@@ -0,0 +1,92 @@
+// tests/shim_overhead.rs
+// 守护 shim 的启动开销：构建脚本会成千上万次地调用 node 等命令，
+// shim 转发相对于直接执行的额外耗时必须保持在很低的水平
+//
+// 耗时受机器负载影响，计时测试默认不运行，需要时单独执行：
+// cargo test --release --test shim_overhead -- --ignored --nocapture
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 40;
+
+/// 每次调用允许的平均额外耗时（调试构建下也应远低于此值）
+const MAX_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// 在 home 中创建一个假的 node 安装（内容为 script 的脚本）并设为全局版本，返回 (node, shim)
+fn fake_node(home: &Path, script: &str) -> (PathBuf, PathBuf) {
+    let enman_root = home.join(".enman");
+
+    let bin = enman_root.join("installs/node/18.17.0/bin");
+    fs::create_dir_all(&bin).unwrap();
+    let node = bin.join("node");
+    fs::write(&node, script).unwrap();
+    fs::set_permissions(&node, fs::Permissions::from_mode(0o755)).unwrap();
+
+    fs::create_dir_all(enman_root.join("global")).unwrap();
+    fs::write(enman_root.join("global/node"), "node@18.17.0").unwrap();
+
+    let shims = enman_root.join("shims");
+    fs::create_dir_all(&shims).unwrap();
+    let shim = shims.join("node");
+    symlink(env!("CARGO_BIN_EXE_enman"), &shim).unwrap();
+    (node, shim)
+}
+
+fn command(program: &Path, home: &Path) -> Command {
+    let mut command = Command::new(program);
+    command
+        .env("HOME", home)
+        .env_remove("ENMAN_HOME")
+        .env_remove("ENMAN_XDG")
+        .env_remove("ENMAN_NODE_VERSION")
+        .env_remove("ENMAN_PROFILE")
+        .current_dir(home);
+    command
+}
+
+fn average_runtime(program: &Path, home: &Path) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let status = command(program, home).arg("--version").status().expect("failed to run program");
+        assert!(status.success(), "{} exited with {}", program.display(), status);
+    }
+    start.elapsed() / RUNS
+}
+
+#[test]
+fn shim_forwards_arguments_and_exit_code() {
+    let home = tempfile::tempdir().unwrap();
+    let (_, shim) = fake_node(home.path(), "#!/bin/sh\nprintf '%s|' \"$@\"\nexit 3\n");
+
+    let output = command(&shim, home.path()).args(["a b", "--flag"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a b|--flag|");
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+#[ignore = "timing benchmark; run explicitly with --ignored"]
+fn shim_overhead_is_small() {
+    let home = tempfile::tempdir().unwrap();
+    // 直接退出的小脚本
+    let (node, shim) = fake_node(home.path(), "#!/bin/sh\nexit 0\n");
+
+    // 预热一次，排除首次加载可执行文件的耗时
+    average_runtime(&shim, home.path());
+
+    let direct = average_runtime(&node, home.path());
+    let via_shim = average_runtime(&shim, home.path());
+    let overhead = via_shim.saturating_sub(direct);
+    println!("direct: {:?}, via shim: {:?}, overhead: {:?}", direct, via_shim, overhead);
+
+    assert!(
+        overhead < MAX_OVERHEAD,
+        "shim overhead {:?} per invocation exceeds {:?}",
+        overhead,
+        MAX_OVERHEAD
+    );
+}